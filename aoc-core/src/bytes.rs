@@ -0,0 +1,132 @@
+//! Helpers for solutions working on the raw `&[u8]` input instead of a `&str`.
+
+/// Iterator over the lines of a byte slice, behaving like [`str::lines`]: lines end with `\n` or
+/// `\r\n`, and the final line ending is optional.
+pub struct Lines<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        match self.rest.iter().position(|&b| b == b'\n') {
+            Some(i) => {
+                let line = &self.rest[..i];
+                self.rest = &self.rest[i + 1..];
+                Some(line.strip_suffix(b"\r").unwrap_or(line))
+            }
+            // Like a bare `\r` anywhere else, a final one isn't a line ending
+            None => Some(std::mem::take(&mut self.rest)),
+        }
+    }
+}
+
+pub fn lines(input: &[u8]) -> Lines<'_> {
+    Lines { rest: input }
+}
+
+/// Parses an unsigned decimal number, returning `None` if `digits` is empty or contains anything
+/// else than ASCII digits.
+pub fn parse_uint(digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut n: u64 = 0;
+    for &b in digits {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        n = n.checked_mul(10)?.checked_add((b - b'0') as u64)?;
+    }
+    Some(n)
+}
+
+/// Iterator over all the unsigned numbers of a byte slice, skipping whatever separates them.
+///
+/// `b"move 3 from 12 to 4"` yields 3, 12 and 4.
+///
+/// # Panics
+///
+/// On a number too large for a `u64`, rather than ending early with only the numbers before it.
+pub struct Numbers<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for Numbers<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.rest.iter().position(u8::is_ascii_digit)?;
+        let len = self.rest[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(self.rest.len() - start);
+
+        let (digits, rest) = self.rest[start..].split_at(len);
+        self.rest = rest;
+        match parse_uint(digits) {
+            Some(n) => Some(n),
+            None => panic!(
+                "Number too large for a u64: {}",
+                String::from_utf8_lossy(digits)
+            ),
+        }
+    }
+}
+
+pub fn numbers(input: &[u8]) -> Numbers<'_> {
+    Numbers { rest: input }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_like_str_lines() {
+        for input in [
+            "a\nb",
+            "a\nb\n",
+            "a\r\nb\r\n",
+            "\n\na\n\nb",
+            "",
+            "\n",
+            "a\r",
+        ] {
+            let bytes: Vec<&[u8]> = lines(input.as_bytes()).collect();
+            let text: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+            assert_eq!(bytes, text, "lines of {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_uint_digits_only() {
+        assert_eq!(parse_uint(b"0"), Some(0));
+        assert_eq!(parse_uint(b"18446744073709551615"), Some(u64::MAX));
+        assert_eq!(parse_uint(b"18446744073709551616"), None);
+        assert_eq!(parse_uint(b""), None);
+        assert_eq!(parse_uint(b"12a"), None);
+        assert_eq!(parse_uint(b"-1"), None);
+    }
+
+    #[test]
+    fn numbers_skip_separators() {
+        let ns: Vec<u64> = numbers(b"move 3 from 12 to 4").collect();
+        assert_eq!(ns, [3, 12, 4]);
+        let ns: Vec<u64> = numbers(b"1-2,30\r\n007").collect();
+        assert_eq!(ns, [1, 2, 30, 7]);
+        assert_eq!(numbers(b"no numbers").next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Number too large for a u64: 99999999999999999999")]
+    fn numbers_panic_on_overflow() {
+        numbers(b"1 99999999999999999999 2").for_each(drop);
+    }
+}
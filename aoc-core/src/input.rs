@@ -0,0 +1,83 @@
+use anyhow::Result;
+use memmap2::Mmap;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Puzzle input, memory-mapped from the cache file when possible.
+///
+/// Solutions never see this type directly: the generated runner converts it into whatever their
+/// `run`/`run2` functions take (`&str` or `&[u8]`) through [`FromInput`].
+pub enum Input {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Input {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+
+        // Mapping an empty file fails on some platforms; there is nothing to share anyway
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::Owned(vec![]));
+        }
+
+        // SAFETY: the runner never modifies a cache file in place: downloads are written to a
+        // temporary file renamed over it, and `clearcache` unlinks it, which leaves the existing
+        // mappings intact on Unix (and fails on Windows while it is mapped). Editing a cached input
+        // by hand while a part runs is not supported: the solution could see its content change,
+        // or the runner crash (SIGBUS) if it gets shorter.
+        Ok(Self::Mapped(unsafe { Mmap::map(&file)? }))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Mapped(m) => m,
+            Self::Owned(v) => v,
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(self.as_bytes())?)
+    }
+}
+
+impl Deref for Input {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_bytes()
+    }
+}
+
+impl From<String> for Input {
+    fn from(value: String) -> Self {
+        Self::Owned(value.into_bytes())
+    }
+}
+
+impl From<&str> for Input {
+    fn from(value: &str) -> Self {
+        Self::Owned(value.as_bytes().to_vec())
+    }
+}
+
+/// Conversion from the raw puzzle input to the argument type of a solution function.
+///
+/// Byte-oriented solutions (`fn run(input: &[u8])`) get the mapped cache file as is, text
+/// solutions (`fn run(input: &str)`) pay for the UTF-8 validation.
+pub trait FromInput<'a>: Sized {
+    fn from_input(input: &'a Input) -> Result<Self>;
+}
+
+impl<'a> FromInput<'a> for &'a [u8] {
+    fn from_input(input: &'a Input) -> Result<Self> {
+        Ok(input.as_bytes())
+    }
+}
+
+impl<'a> FromInput<'a> for &'a str {
+    fn from_input(input: &'a Input) -> Result<Self> {
+        input.as_str()
+    }
+}
@@ -1,58 +1,187 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use reqwest::{blocking, header};
 use std::fmt::Display;
-use std::fs::OpenOptions;
-use std::io::Read;
 use std::path::PathBuf;
-use std::str::FromStr;
 
-pub static CACHE_DIR: &str = "downloaded_inputs";
+pub mod bytes;
+mod input;
+mod puzzle_id;
+mod registry;
+mod settings;
+mod solution;
+
+pub use input::{FromInput, Input};
+pub use puzzle_id::PuzzleId;
+pub use registry::{registry, Registry};
+pub use settings::{configure, settings, Settings, CACHE_DIR};
+pub use solution::{block_on, IntoAnswer, Solver};
+
+#[doc(hidden)]
+pub use linkme;
 
 pub trait AdventOfCodeRunnable
 where
-    Self: Display,
+    Self: Display + Sync,
 {
-    fn matches(&self, day: u8, year: u16) -> bool;
-    fn get_input(&self, oauth_session_id: &str, is_second: bool) -> Result<String>;
-    fn run(&self, input: &str) -> Result<String>;
-    fn run2(&self, input: &str) -> Result<String>;
-}
-
-pub fn get_input(session_id: &str, day: u8, year: u16, _is_second: bool) -> Result<String> {
-    let local_cached_file = PathBuf::from_str(&format!("{}/{}_{}.txt", CACHE_DIR, year, day))?;
-
-    let mut input = String::new();
-    if local_cached_file.is_file() {
-        // Read cached input
-        OpenOptions::new()
-            .read(true)
-            .open(local_cached_file)?
-            .read_to_string(&mut input)?;
-    } else {
+    fn id(&self) -> PuzzleId;
+    fn title(&self) -> &'static str;
+    fn tags(&self) -> &'static [&'static str];
+    fn is_implemented(&self, part: u8) -> bool;
+    fn is_confirmed(&self, part: u8) -> bool;
+
+    fn matches(&self, id: &PuzzleId) -> bool {
+        self.id().same_day(id)
+    }
+
+    fn is_input_cached(&self) -> bool {
+        cached_input_path(&self.id()).is_file()
+    }
+
+    fn get_input(&self, oauth_session_id: &str, is_second: bool) -> Result<Input> {
+        get_input(oauth_session_id, &self.id(), is_second)
+    }
+
+    fn run(&self, input: &Input) -> Result<String>;
+    fn run2(&self, input: &Input) -> Result<String>;
+}
+
+/// Every exercise annotated with `#[advent_of_code]`, gathered at link time in no particular
+/// order; see [`registry`].
+#[linkme::distributed_slice]
+pub static EXERCISES: [&'static dyn AdventOfCodeRunnable];
+
+/// The tables of the exercises of a crate, generated by its build script to spare sorting them;
+/// see [`registry`].
+#[linkme::distributed_slice]
+pub static TABLES: [&'static Registry];
+
+/// All the registered exercises, sorted by puzzle.
+pub fn exercises() -> &'static [&'static dyn AdventOfCodeRunnable] {
+    registry().as_slice()
+}
+
+/// Every variant registered with `#[aoc_solver]`, gathered at link time in no particular order;
+/// see [`variants`].
+#[linkme::distributed_slice]
+pub static SOLVERS: [Solver];
+
+/// The registered variants of the given part of an exercise, sorted by name.
+pub fn variants(id: &PuzzleId, part: u8) -> Vec<&'static Solver> {
+    let mut vs: Vec<&Solver> = SOLVERS
+        .iter()
+        .filter(|s| s.id().same_day(id) && s.part == part)
+        .collect();
+    vs.sort_by_key(|s| s.variant);
+    vs
+}
+
+pub fn cached_input_path(id: &PuzzleId) -> PathBuf {
+    settings()
+        .cache_dir
+        .join(format!("{}_{}.txt", id.year(), id.day()))
+}
+
+pub fn get_input(session_id: &str, id: &PuzzleId, _is_second: bool) -> Result<Input> {
+    let local_cached_file = cached_input_path(id);
+
+    if !local_cached_file.is_file() {
+        if settings().offline {
+            bail!("The input of {id} is not cached, and downloads are disabled (offline mode)");
+        }
+
         // Download the input
         let mut headers = header::HeaderMap::new();
         headers.insert(
             header::COOKIE,
             header::HeaderValue::from_str(&format!("session={}", session_id))?,
         );
-        let c = blocking::ClientBuilder::default()
-            .default_headers(headers)
-            .build()?;
+        let c = client(headers)?;
 
-        input = c
+        let input = c
             .execute(
                 c.get(format!(
                     "https://adventofcode.com/{}/day/{}/input",
-                    year, day
+                    id.year(),
+                    id.day()
                 ))
                 .build()?,
             )?
             .error_for_status()?
-            .text()?;
+            .bytes()?;
+
+        // Cache it, without ever truncating a file which could be mapped, see Input::open
+        std::fs::create_dir_all(&settings().cache_dir)?;
+        let partial_file = local_cached_file.with_extension("txt.part");
+        std::fs::write(&partial_file, input)?;
+        std::fs::rename(&partial_file, &local_cached_file)?;
+    }
+
+    // Map the cached input
+    Input::open(&local_cached_file)
+}
+
+pub fn cached_puzzle_path(id: &PuzzleId) -> PathBuf {
+    settings()
+        .cache_dir
+        .join(format!("{}_{}.html", id.year(), id.day()))
+}
 
-        // Cache it
-        std::fs::write(local_cached_file, &input)?;
+/// The HTML page of the puzzle, which only has the second part once logged in with the session
+/// of an account which solved the first one. The page is cached, and downloaded again with a
+/// session as long as it lacks the second part; the cached one is kept if that fails.
+pub fn get_puzzle_page(id: &PuzzleId, session_id: Option<&str>) -> Result<String> {
+    let local_cached_file = cached_puzzle_path(id);
+    let cached = std::fs::read_to_string(&local_cached_file).ok();
+    match &cached {
+        Some(page) if page.contains("id=\"part2\"") || session_id.is_none() => {
+            return Ok(page.clone())
+        }
+        Some(page) if settings().offline => return Ok(page.clone()),
+        None if settings().offline => {
+            bail!("The page of {id} is not cached, and downloads are disabled (offline mode)")
+        }
+        _ => {}
     }
 
-    Ok(input)
+    let download = || -> Result<String> {
+        let mut headers = header::HeaderMap::new();
+        if let Some(session_id) = session_id {
+            headers.insert(
+                header::COOKIE,
+                header::HeaderValue::from_str(&format!("session={session_id}"))?,
+            );
+        }
+        Ok(client(headers)?
+            .get(format!(
+                "https://adventofcode.com/{}/day/{}",
+                id.year(),
+                id.day()
+            ))
+            .send()?
+            .error_for_status()?
+            .text()?)
+    };
+    let page = match (download(), cached) {
+        (Ok(page), _) => page,
+        (Err(_), Some(cached)) => return Ok(cached),
+        (Err(e), None) => return Err(e),
+    };
+
+    std::fs::create_dir_all(&settings().cache_dir)?;
+    let partial_file = local_cached_file.with_extension("html.part");
+    std::fs::write(&partial_file, &page)?;
+    std::fs::rename(&partial_file, &local_cached_file)?;
+    Ok(page)
+}
+
+fn client(mut headers: header::HeaderMap) -> Result<blocking::Client> {
+    if let Some(user_agent) = &settings().user_agent {
+        headers.insert(
+            header::USER_AGENT,
+            header::HeaderValue::from_str(user_agent)?,
+        );
+    }
+    Ok(blocking::ClientBuilder::default()
+        .default_headers(headers)
+        .build()?)
 }
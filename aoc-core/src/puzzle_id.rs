@@ -0,0 +1,235 @@
+use anyhow::{bail, Context, Result};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A valid Advent of Code puzzle: a year since the first event (2015), a day from 1 to 25, and
+/// optionally one of the two parts of that day.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PuzzleId {
+    year: u16,
+    day: u8,
+    part: Option<u8>,
+}
+
+impl PuzzleId {
+    pub const FIRST_YEAR: u16 = 2015;
+    pub const LAST_DAY: u8 = 25;
+
+    pub fn new(year: u16, day: u8) -> Result<Self> {
+        Ok(Self {
+            year: Self::check_year(year)?,
+            day: Self::check_day(day)?,
+            part: None,
+        })
+    }
+
+    pub fn with_part(self, part: u8) -> Result<Self> {
+        Ok(Self {
+            part: Some(Self::check_part(part)?),
+            ..self
+        })
+    }
+
+    pub fn check_year(year: u16) -> Result<u16> {
+        if year < Self::FIRST_YEAR {
+            bail!("Year must be {} or later, got {year}.", Self::FIRST_YEAR);
+        }
+        Ok(year)
+    }
+
+    pub fn check_day(day: u8) -> Result<u8> {
+        if !(1..=Self::LAST_DAY).contains(&day) {
+            bail!(
+                "Day must be from 1 to {} included, got {day}.",
+                Self::LAST_DAY
+            );
+        }
+        Ok(day)
+    }
+
+    pub fn check_part(part: u8) -> Result<u8> {
+        if !(1..=2).contains(&part) {
+            bail!("Part must be 1 or 2, got {part}.");
+        }
+        Ok(part)
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn part(&self) -> Option<u8> {
+        self.part
+    }
+
+    /// Whether both ids designate the same day, regardless of their parts.
+    pub fn same_day(&self, other: &Self) -> bool {
+        self.year == other.year && self.day == other.day
+    }
+
+    /// The puzzle of today during an event, whose puzzles unlock at midnight US Eastern time
+    /// (always UTC-5 in December).
+    pub fn today() -> Option<Self> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        Self::on(now.as_secs() as i64)
+    }
+
+    /// The puzzle of the day of the given Unix time, during an event.
+    fn on(timestamp: i64) -> Option<Self> {
+        let eastern = timestamp - 5 * 3600;
+        let (year, month, day) = civil_from_days(eastern.div_euclid(24 * 3600));
+        match month {
+            12 => Self::new(year as u16, day as u8).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// The (year, month, day) of the given number of days since 1970-01-01, in the proleptic
+/// Gregorian calendar (see http://howardhinnant.github.io/date_algorithms.html#civil_from_days).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+impl Display for PuzzleId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.year, self.day)?;
+        if let Some(part) = self.part {
+            write!(f, "/{part}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `YEAR/DAY` or `YEAR/DAY/PART`, with `-` also accepted as separator and zero-padded
+/// numbers, e.g. `2022/7` or `2022-07-2`.
+impl FromStr for PuzzleId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let fields: Vec<&str> = s.trim().split(['/', '-']).collect();
+        if !(2..=3).contains(&fields.len()) {
+            bail!("Expected a puzzle like 2022/7 or 2022-07-2, got {s:?}.");
+        }
+
+        let year = fields[0]
+            .parse()
+            .with_context(|| format!("Invalid year {:?}", fields[0]))?;
+        let day = fields[1]
+            .parse()
+            .with_context(|| format!("Invalid day {:?}", fields[1]))?;
+
+        let id = Self::new(year, day)?;
+        match fields.get(2) {
+            Some(part) => id.with_part(
+                part.parse()
+                    .with_context(|| format!("Invalid part {:?}", part))?,
+            ),
+            None => Ok(id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_separators_and_padding() {
+        let day = PuzzleId::new(2022, 7).unwrap();
+        assert_eq!("2022/7".parse::<PuzzleId>().unwrap(), day);
+        assert_eq!("2022-07".parse::<PuzzleId>().unwrap(), day);
+        assert_eq!(
+            " 2022/07/2 ".parse::<PuzzleId>().unwrap(),
+            day.with_part(2).unwrap()
+        );
+        assert_eq!(
+            "2022-7/1".parse::<PuzzleId>().unwrap(),
+            day.with_part(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_puzzles() {
+        for s in [
+            "",
+            "2022",
+            "2022/7/1/1",
+            "2014/1",
+            "2022/0",
+            "2022/26",
+            "2022/7/3",
+            "2022/x",
+            "a/7",
+            "2022//7",
+        ] {
+            assert!(s.parse::<PuzzleId>().is_err(), "{s:?} was parsed");
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in ["2015/1", "2022/25", "2022/7/2"] {
+            assert_eq!(s.parse::<PuzzleId>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(19327), (2022, 12, 1));
+        assert_eq!(civil_from_days(19357), (2022, 12, 31));
+        // Leap years, including the every-400-years one, but not the every-100-years ones
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19782), (2024, 2, 29));
+        assert_eq!(civil_from_days(19417), (2023, 3, 1));
+        assert_eq!(civil_from_days(-25508), (1900, 3, 1));
+        assert_eq!(civil_from_days(-25509), (1900, 2, 28));
+        assert_eq!(civil_from_days(47541), (2100, 3, 1));
+        assert_eq!(civil_from_days(47540), (2100, 2, 28));
+    }
+
+    #[test]
+    fn puzzles_unlock_at_midnight_eastern() {
+        // 2022-12-01 05:00 UTC
+        let dec_1 = 1669870800;
+        assert_eq!(PuzzleId::on(dec_1 - 1), None);
+        assert_eq!(PuzzleId::on(dec_1), PuzzleId::new(2022, 1).ok());
+        assert_eq!(
+            PuzzleId::on(dec_1 + 24 * 3600 - 1),
+            PuzzleId::new(2022, 1).ok()
+        );
+        assert_eq!(PuzzleId::on(dec_1 + 24 * 3600), PuzzleId::new(2022, 2).ok());
+        let dec_26 = dec_1 + 25 * 24 * 3600;
+        assert_eq!(PuzzleId::on(dec_26 - 1), PuzzleId::new(2022, 25).ok());
+        assert_eq!(PuzzleId::on(dec_26), None);
+        // 2014-12-05, before the first event
+        assert_eq!(PuzzleId::on(1417755600), None);
+    }
+
+    #[test]
+    fn order_by_year_day_part() {
+        let id = |s: &str| s.parse::<PuzzleId>().unwrap();
+        assert!(id("2021/25") < id("2022/1"));
+        assert!(id("2022/2") < id("2022/10"));
+        assert!(id("2022/7") < id("2022/7/1"));
+        assert!(id("2022/7/1") < id("2022/7/2"));
+        assert!(id("2022/7/1").same_day(&id("2022/7/2")));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{AdventOfCodeRunnable, PuzzleId, EXERCISES, TABLES};
+use std::borrow::Cow;
+use std::ops::{Bound, RangeBounds};
+use std::sync::OnceLock;
+
+type Exercise = &'static dyn AdventOfCodeRunnable;
+
+/// The registered exercises, sorted by puzzle and indexed by day; lookups, iteration and range
+/// queries don't allocate.
+///
+/// The table of a crate's own days is generated by its build script, see [`TABLES`]. Exercises
+/// registered from modules no build script knows about are only gathered by linkme in link order,
+/// so [`registry`] then sorts them once, on its first call.
+pub struct Registry {
+    exercises: Cow<'static, [Exercise]>,
+    /// Position in `exercises` of every day since the first event, if registered
+    index: Cow<'static, [Option<usize>]>,
+}
+
+impl Registry {
+    fn new(exercises: &[Exercise]) -> Self {
+        let mut exercises = exercises.to_vec();
+        exercises.sort_by_key(|e| e.id());
+
+        let n_days = exercises
+            .last()
+            .map_or(0, |e| Self::slot(e.id().year(), e.id().day()) + 1);
+        let mut index = vec![None; n_days];
+        for (i, e) in exercises.iter().enumerate() {
+            index[Self::slot(e.id().year(), e.id().day())] = Some(i);
+        }
+
+        Self {
+            exercises: exercises.into(),
+            index: index.into(),
+        }
+    }
+
+    /// A registry of exercises sorted by puzzle, with the position of every day in its index, see
+    /// [`slot`](Self::slot).
+    pub const fn from_table(
+        exercises: &'static [Exercise],
+        index: &'static [Option<usize>],
+    ) -> Self {
+        Self {
+            exercises: Cow::Borrowed(exercises),
+            index: Cow::Borrowed(index),
+        }
+    }
+
+    /// Position of a day in the index of a registry.
+    pub const fn slot(year: u16, day: u8) -> usize {
+        (year - PuzzleId::FIRST_YEAR) as usize * PuzzleId::LAST_DAY as usize + (day - 1) as usize
+    }
+
+    /// The exercise of the given day, regardless of the part of the id.
+    pub fn get(&self, id: &PuzzleId) -> Option<Exercise> {
+        let i = (*self.index.get(Self::slot(id.year(), id.day()))?)?;
+        Some(self.exercises[i])
+    }
+
+    /// All the exercises, sorted by puzzle.
+    pub fn as_slice(&self) -> &[Exercise] {
+        &self.exercises
+    }
+
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, Exercise>> {
+        self.exercises.iter().copied()
+    }
+
+    /// The exercises whose day is within the range, sorted. Parts of the bounds are ignored.
+    pub fn range(&self, range: impl RangeBounds<PuzzleId>) -> &[Exercise] {
+        let start = match range.start_bound() {
+            Bound::Included(id) => self.position_before(id),
+            Bound::Excluded(id) => self.position_after(id),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(id) => self.position_after(id),
+            Bound::Excluded(id) => self.position_before(id),
+            Bound::Unbounded => self.exercises.len(),
+        };
+        &self.exercises[start..end.max(start)]
+    }
+
+    /// Position of the first exercise from the given day on.
+    fn position_before(&self, id: &PuzzleId) -> usize {
+        self.exercises
+            .partition_point(|e| (e.id().year(), e.id().day()) < (id.year(), id.day()))
+    }
+
+    /// Position of the first exercise after the given day.
+    fn position_after(&self, id: &PuzzleId) -> usize {
+        self.exercises
+            .partition_point(|e| (e.id().year(), e.id().day()) <= (id.year(), id.day()))
+    }
+
+    /// The exercises of the given year, sorted by day.
+    pub fn year(&self, year: u16) -> &[Exercise] {
+        match (
+            PuzzleId::new(year, 1),
+            PuzzleId::new(year, PuzzleId::LAST_DAY),
+        ) {
+            (Ok(first), Ok(last)) => self.range(first..=last),
+            _ => &[],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.exercises.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exercises.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a Registry {
+    type Item = Exercise;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'a, Exercise>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The registry of all the exercises annotated with `#[advent_of_code]`: the table generated at
+/// build time if it has them all, or else one sorted at runtime.
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<&'static Registry> = OnceLock::new();
+    static SORTED: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| match TABLES[..] {
+        [table] if table.len() == EXERCISES.len() => table,
+        _ => SORTED.get_or_init(|| Registry::new(&EXERCISES)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Input;
+    use anyhow::Result;
+    use std::fmt::{Display, Formatter};
+
+    struct Day(u16, u8);
+
+    impl Display for Day {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.id())
+        }
+    }
+
+    impl AdventOfCodeRunnable for Day {
+        fn id(&self) -> PuzzleId {
+            PuzzleId::new(self.0, self.1).unwrap()
+        }
+        fn title(&self) -> &'static str {
+            ""
+        }
+        fn tags(&self) -> &'static [&'static str] {
+            &[]
+        }
+        fn is_implemented(&self, _part: u8) -> bool {
+            true
+        }
+        fn is_confirmed(&self, _part: u8) -> bool {
+            false
+        }
+        fn run(&self, _input: &Input) -> Result<String> {
+            Ok(String::new())
+        }
+        fn run2(&self, _input: &Input) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    static DAYS: [Day; 6] = [
+        Day(2022, 7),
+        Day(2015, 1),
+        Day(2022, 1),
+        Day(2021, 25),
+        Day(2022, 25),
+        Day(2022, 2),
+    ];
+
+    fn registry() -> Registry {
+        let exercises: Vec<Exercise> = DAYS.iter().map(|d| d as Exercise).collect();
+        Registry::new(&exercises)
+    }
+
+    fn id(s: &str) -> PuzzleId {
+        s.parse().unwrap()
+    }
+
+    fn ids(es: &[Exercise]) -> Vec<String> {
+        es.iter().map(|e| e.id().to_string()).collect()
+    }
+
+    #[test]
+    fn sorted_by_puzzle() {
+        let r = registry();
+        assert_eq!(
+            ids(r.as_slice()),
+            ["2015/1", "2021/25", "2022/1", "2022/2", "2022/7", "2022/25"]
+        );
+        assert_eq!(r.len(), 6);
+        assert!(Registry::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn get_ignores_parts() {
+        let r = registry();
+        assert_eq!(r.get(&id("2022/7")).unwrap().id(), id("2022/7"));
+        assert_eq!(r.get(&id("2022/7/2")).unwrap().id(), id("2022/7"));
+        assert_eq!(r.get(&id("2015/1/1")).unwrap().id(), id("2015/1"));
+        assert!(r.get(&id("2022/8")).is_none());
+        // Beyond the last registered day
+        assert!(r.get(&id("2023/1")).is_none());
+    }
+
+    #[test]
+    fn range_bounds() {
+        let r = registry();
+        assert_eq!(
+            ids(r.range(id("2022/2")..id("2022/25"))),
+            ["2022/2", "2022/7"]
+        );
+        assert_eq!(
+            ids(r.range(id("2022/2")..=id("2022/25"))),
+            ["2022/2", "2022/7", "2022/25"]
+        );
+        assert_eq!(ids(r.range(..id("2022/1"))), ["2015/1", "2021/25"]);
+        assert_eq!(ids(r.range(id("2022/8")..)), ["2022/25"]);
+        assert_eq!(
+            ids(r.range((Bound::Excluded(id("2022/2")), Bound::Unbounded))),
+            ["2022/7", "2022/25"]
+        );
+        assert!(r.range(id("2022/3")..id("2022/7")).is_empty());
+        assert!(r.range(id("2022/7")..id("2022/2")).is_empty());
+    }
+
+    #[test]
+    fn range_ignores_parts() {
+        let r = registry();
+        assert_eq!(
+            ids(r.range(id("2022/2/2")..=id("2022/7/1"))),
+            ["2022/2", "2022/7"]
+        );
+        assert_eq!(ids(r.range(id("2022/2/1")..id("2022/7/2"))), ["2022/2"]);
+    }
+
+    #[test]
+    fn generated_tables() {
+        // Like the table generated by a build script
+        static SORTED: [Exercise; 6] = [&DAYS[1], &DAYS[3], &DAYS[2], &DAYS[5], &DAYS[0], &DAYS[4]];
+        static INDEX: [Option<usize>; Registry::slot(2022, 25) + 1] = {
+            let mut index = [None; Registry::slot(2022, 25) + 1];
+            index[Registry::slot(2015, 1)] = Some(0);
+            index[Registry::slot(2021, 25)] = Some(1);
+            index[Registry::slot(2022, 1)] = Some(2);
+            index[Registry::slot(2022, 2)] = Some(3);
+            index[Registry::slot(2022, 7)] = Some(4);
+            index[Registry::slot(2022, 25)] = Some(5);
+            index
+        };
+        static TABLE: Registry = Registry::from_table(&SORTED, &INDEX);
+
+        let r = registry();
+        assert_eq!(ids(TABLE.as_slice()), ids(r.as_slice()));
+        assert_eq!(TABLE.index, r.index);
+        assert_eq!(TABLE.get(&id("2022/7")).unwrap().id(), id("2022/7"));
+        assert_eq!(ids(TABLE.year(2022)), ids(r.year(2022)));
+    }
+
+    #[test]
+    fn year() {
+        let r = registry();
+        assert_eq!(ids(r.year(2022)), ["2022/1", "2022/2", "2022/7", "2022/25"]);
+        assert_eq!(ids(r.year(2021)), ["2021/25"]);
+        assert!(r.year(2016).is_empty());
+        assert!(r.year(2014).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub static CACHE_DIR: &str = "downloaded_inputs";
+
+/// Where the inputs are cached, and how they are downloaded.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub cache_dir: PathBuf,
+    /// Never download anything: inputs must already be cached
+    pub offline: bool,
+    /// Sent with every request, so that adventofcode.com can contact the author of the tool
+    pub user_agent: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            cache_dir: PathBuf::from(CACHE_DIR),
+            offline: false,
+            user_agent: None,
+        }
+    }
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Sets the settings, once and before any input is fetched.
+pub fn configure(settings: Settings) -> Result<()> {
+    SETTINGS
+        .set(settings)
+        .map_err(|_| anyhow!("The settings are already in use"))
+}
+
+/// The configured settings, or the default ones.
+pub fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
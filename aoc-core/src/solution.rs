@@ -0,0 +1,92 @@
+use crate::{Input, PuzzleId};
+use anyhow::Result;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Conversion of whatever a solution function returns into the answer displayed by the runner.
+///
+/// Solutions can return their answer directly (`String` or any integer) when they can't fail, or
+/// wrap it in a `Result` whose error converts into an `anyhow::Error`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not an answer",
+    note = "solutions return a String, an integer, or a Result of one"
+)]
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<String>;
+}
+
+impl IntoAnswer for String {
+    fn into_answer(self) -> Result<String> {
+        Ok(self)
+    }
+}
+
+impl IntoAnswer for &str {
+    fn into_answer(self) -> Result<String> {
+        Ok(self.to_string())
+    }
+}
+
+macro_rules! impl_into_answer_for_integers {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn into_answer(self) -> Result<String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer_for_integers!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T: IntoAnswer, E: Into<anyhow::Error>> IntoAnswer for std::result::Result<T, E> {
+    fn into_answer(self) -> Result<String> {
+        self.map_err(Into::into)?.into_answer()
+    }
+}
+
+/// An alternative implementation of one part of an exercise, registered with `#[aoc_solver]`.
+pub struct Solver {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    pub solve: fn(&Input) -> Result<String>,
+}
+
+impl Solver {
+    pub fn id(&self) -> PuzzleId {
+        PuzzleId::new(self.year, self.day)
+            .and_then(|id| id.with_part(self.part))
+            .expect("checked by aoc_solver")
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs an `async` solution to completion on the current thread.
+///
+/// Solutions have nothing to wait for but their own computations, so this does not need more than
+/// parking the thread until the future is woken up.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Discovers the `src/aoc_<year>_<day>.rs` modules and registers an exercise for each of them, so
+//! that adding a day is only a matter of creating its module.
+//!
+//! The generated structs are named after the titles of the days, and give their names to the
+//! exercises. Days whose struct is written by hand in `main.rs` are left alone, so that a struct
+//! can keep its name when the title changes.
+//!
+//! All of them, generated or not, make the sorted table of `aoc_core::registry()`.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::path::Path;
+use std::{env, fs};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Lit, Token};
+
+struct Day {
+    year: u16,
+    day: u8,
+    struct_name: String,
+    has_title: bool,
+}
+
+/// Parses the year and day out of a file name like `aoc_2022_7.rs`.
+fn parse_file_name(name: &str) -> Option<(u16, u8)> {
+    let mut fields = name.strip_prefix("aoc_")?.strip_suffix(".rs")?.split('_');
+    let year = fields.next()?.parse().ok()?;
+    let day = fields.next()?.parse().ok()?;
+    match fields.next() {
+        None => Some((year, day)),
+        Some(_) => None,
+    }
+}
+
+/// The value of the module's `TITLE` constant, if any.
+fn find_title(module: &syn::File) -> Option<String> {
+    module.items.iter().find_map(|item| match item {
+        syn::Item::Const(c) if c.ident == "TITLE" => match &*c.expr {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The year, day and struct name of the `#[advent_of_code]` structs written by hand in `main.rs`.
+fn declared_days(main: &syn::File) -> Vec<(u16, u8, String)> {
+    let int = |expr: &Expr| match expr {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(i), ..
+        }) => i.base10_parse::<u16>().ok(),
+        _ => None,
+    };
+
+    let mut days = vec![];
+    for item in &main.items {
+        let s = match item {
+            syn::Item::Struct(s) => s,
+            _ => continue,
+        };
+        let attr = s.attrs.iter().find(|a| {
+            a.path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "advent_of_code")
+        });
+        let args = match attr
+            .map(|a| a.parse_args_with(Punctuated::<Expr, Token![,]>::parse_terminated))
+        {
+            Some(Ok(args)) => args,
+            _ => continue,
+        };
+
+        // Like the attribute: day and year either positional, or named
+        let (mut day, mut year) = (None, None);
+        let mut positional = args.iter().filter_map(int);
+        if let Some(d) = positional.next() {
+            day = Some(d);
+            year = positional.next();
+        }
+        for arg in &args {
+            if let Expr::Assign(assign) = arg {
+                match &*assign.left {
+                    Expr::Path(p) if p.path.is_ident("day") => day = int(&assign.right),
+                    Expr::Path(p) if p.path.is_ident("year") => year = int(&assign.right),
+                    _ => {}
+                }
+            }
+        }
+        // An invalid day is left to the attribute to report
+        if let (Some(day), Some(year)) = (day.and_then(|d| u8::try_from(d).ok()), year) {
+            days.push((year, day, s.ident.to_string()));
+        }
+    }
+    days
+}
+
+/// Turns a title like `No Space Left On Device` into `NoSpaceLeftOnDevice`.
+fn struct_name_from_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_ascii_uppercase();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect()
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    // Invalid code is left for the compiler to complain about
+    let declared = fs::read_to_string("src/main.rs")
+        .ok()
+        .and_then(|content| syn::parse_file(&content).ok())
+        .map(|main| declared_days(&main))
+        .unwrap_or_default();
+    let mut names: HashSet<String> = declared.iter().map(|d| d.2.clone()).collect();
+
+    let mut days = vec![];
+    for entry in fs::read_dir("src").expect("src directory should be readable") {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let (year, day) = match parse_file_name(&name) {
+            Some(id) => id,
+            None => continue,
+        };
+        if declared.iter().any(|d| (d.0, d.1) == (year, day)) {
+            continue;
+        }
+
+        // Invalid modules are left for the compiler to complain about
+        let title = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| syn::parse_file(&content).ok())
+            .and_then(|module| find_title(&module));
+        let has_title = title.is_some();
+        let struct_name = match title.map(|t| struct_name_from_title(&t)) {
+            Some(name) if name.starts_with(|c: char| c.is_ascii_alphabetic()) => name,
+            _ => format!("Aoc{year}Day{day}"),
+        };
+
+        days.push(Day {
+            year,
+            day,
+            struct_name,
+            has_title,
+        });
+    }
+    days.sort_by_key(|d| (d.year, d.day));
+
+    // A title may come back another year: the struct of the later day is suffixed with its year
+    for d in days.iter_mut() {
+        if names.contains(&d.struct_name) {
+            d.struct_name = format!("{}{}", d.struct_name, d.year);
+        }
+        if names.contains(&d.struct_name) {
+            d.struct_name = format!("Aoc{}Day{}", d.year, d.day);
+        }
+        names.insert(d.struct_name.clone());
+    }
+
+    let mut generated = String::new();
+    for d in days.iter() {
+        // Without a TITLE, the struct's name would make a poor one
+        let title = match d.has_title {
+            true => String::new(),
+            false => format!(", title = \"Day {}\"", d.day),
+        };
+        writeln!(
+            generated,
+            "#[advent_of_code({}, {}{title})]\npub struct {};\n",
+            d.day, d.year, d.struct_name
+        )
+        .unwrap();
+    }
+
+    // Sorted and indexed here rather than at runtime
+    let mut table: Vec<(u16, u8, &str)> = declared
+        .iter()
+        .map(|(year, day, name)| (*year, *day, name.as_str()))
+        .chain(days.iter().map(|d| (d.year, d.day, d.struct_name.as_str())))
+        .collect();
+    table.sort();
+    if let Some((last_year, last_day, _)) = table.last() {
+        let n_slots = format!("aoc_core::Registry::slot({last_year}, {last_day}) + 1");
+        writeln!(
+            generated,
+            "static SORTED_EXERCISES: [&dyn aoc_core::AdventOfCodeRunnable; {}] = [",
+            table.len()
+        )
+        .unwrap();
+        for (_, _, name) in &table {
+            writeln!(generated, "    &{name},").unwrap();
+        }
+        writeln!(
+            generated,
+            "];
+
+static EXERCISE_INDEX: [Option<usize>; {n_slots}] = {{
+    \
+            let mut index = [None; {n_slots}];"
+        )
+        .unwrap();
+        for (i, (year, day, _)) in table.iter().enumerate() {
+            writeln!(
+                generated,
+                "    index[aoc_core::Registry::slot({year}, {day})] = Some({i});"
+            )
+            .unwrap();
+        }
+        writeln!(
+            generated,
+            "    index\n}};\n\n\
+            static EXERCISE_TABLE: aoc_core::Registry =\n    \
+            aoc_core::Registry::from_table(&SORTED_EXERCISES, &EXERCISE_INDEX);\n\n\
+            #[aoc_core::linkme::distributed_slice(aoc_core::TABLES)]\n\
+            #[linkme(crate = aoc_core::linkme)]\n\
+            static EXERCISE_TABLE_REGISTRATION: &aoc_core::Registry = &EXERCISE_TABLE;"
+        )
+        .unwrap();
+    }
+
+    let out_file = Path::new(&env::var("OUT_DIR").unwrap()).join("exercises.rs");
+    fs::write(out_file, generated).unwrap();
+}
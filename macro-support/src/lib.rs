@@ -1,45 +1,369 @@
-use macro_state::{proc_append_state, proc_read_state_vec};
+use aoc_core::PuzzleId;
 use proc_macro::TokenStream;
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
 use std::path::PathBuf;
-use std::str::FromStr;
 use syn::__private::Span;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{parse_macro_input, LitInt, Token};
+use syn::{
+    bracketed, parse_macro_input, FnArg, Ident, Item, ItemFn, Lit, LitInt, LitStr, ReturnType,
+    Signature, Token, Type, Visibility,
+};
+
+/// The names of the arguments given so far to a macro, to reject repeated ones, and positional ones
+/// after named ones.
+#[derive(Default)]
+struct SeenArgs {
+    names: Vec<String>,
+    named: bool,
+}
+
+impl SeenArgs {
+    fn positional(&mut self, name: &str, span: Span) -> syn::Result<()> {
+        if self.named {
+            return Err(syn::Error::new(
+                span,
+                "Positional arguments must come before the named ones",
+            ));
+        }
+        self.insert(name, span)
+    }
+
+    fn named(&mut self, key: &Ident) -> syn::Result<()> {
+        self.named = true;
+        self.insert(&key.to_string(), key.span())
+    }
+
+    fn insert(&mut self, name: &str, span: Span) -> syn::Result<()> {
+        if self.names.iter().any(|n| n == name) {
+            return Err(syn::Error::new(
+                span,
+                format!("Duplicate argument `{name}`"),
+            ));
+        }
+        self.names.push(name.to_string());
+        Ok(())
+    }
+}
 
 struct AOCArgs {
     day: u8,
     year: u16,
+    module: Option<syn::Path>,
+    title: Option<String>,
+    tags: Vec<String>,
+    parts: Option<Vec<u8>>,
+    confirmed: Vec<u8>,
+    part1: Option<Ident>,
+    part2: Option<Ident>,
+}
+
+impl AOCArgs {
+    /// Parses a list of part numbers like `[1, 2]`.
+    fn parse_parts(input: ParseStream) -> syn::Result<Vec<u8>> {
+        let content;
+        bracketed!(content in input);
+        let parts = Punctuated::<LitInt, Token![,]>::parse_terminated(&content)?;
+
+        let mut ret = vec![];
+        for part in parts.iter() {
+            let n = PuzzleId::check_part(part.base10_parse::<u8>()?)
+                .map_err(|e| syn::Error::new(part.span(), e))?;
+            ret.push(n);
+        }
+        Ok(ret)
+    }
+
+    /// Relative path from `src/` to the file of the module implementing the exercise, or
+    /// `aoc_<year>_<day>.rs` by default.
+    fn module_file(&self) -> PathBuf {
+        match &self.module {
+            None => PathBuf::from(format!("aoc_{}_{}.rs", self.year, self.day)),
+            Some(path) => {
+                let mut file: PathBuf = path.segments.iter().map(|s| s.ident.to_string()).collect();
+                file.set_extension("rs");
+                file
+            }
+        }
+    }
 }
 
 impl Parse for AOCArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let ints = Punctuated::<LitInt, Token![,]>::parse_terminated(input)?;
-        if ints.len() != 2 {
-            Err(input.error("Expected day and year like 1, 2022"))
-        } else {
-            let day = ints[0].base10_parse::<u8>()?;
-            if !(1..=25).contains(&day) {
-                return Err(syn::Error::new(
-                    ints[0].span(),
-                    "Day must be from 1 to 31 included.",
-                ));
+        let mut day = None;
+        let mut year = None;
+        let mut args = Self {
+            day: 0,
+            year: 0,
+            module: None,
+            title: None,
+            tags: vec![],
+            parts: None,
+            confirmed: vec![],
+            part1: None,
+            part2: None,
+        };
+
+        // Either day and year as the first positional arguments, like 1, 2022, or named arguments
+        // like day = 1, year = 2022, module = "days::day01", title = "Calorie Counting"...
+        let mut n_positional = 0;
+        let mut seen = SeenArgs::default();
+        while !input.is_empty() {
+            if input.peek(LitInt) {
+                let int: LitInt = input.parse()?;
+                match n_positional {
+                    0 => {
+                        seen.positional("day", int.span())?;
+                        day = Some(int);
+                    }
+                    1 => {
+                        seen.positional("year", int.span())?;
+                        year = Some(int);
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            int.span(),
+                            "Expected day and year like 1, 2022",
+                        ))
+                    }
+                }
+                n_positional += 1;
+            } else {
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                seen.named(&key)?;
+                match key.to_string().as_str() {
+                    "day" => day = Some(input.parse()?),
+                    "year" => year = Some(input.parse()?),
+                    "module" => args.module = Some(input.parse::<LitStr>()?.parse()?),
+                    "title" => args.title = Some(input.parse::<LitStr>()?.value()),
+                    "tags" => {
+                        let content;
+                        bracketed!(content in input);
+                        args.tags = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+                            .iter()
+                            .map(LitStr::value)
+                            .collect();
+                    }
+                    "parts" => args.parts = Some(Self::parse_parts(input)?),
+                    "confirmed" => args.confirmed = Self::parse_parts(input)?,
+                    "part1" => args.part1 = Some(input.parse()?),
+                    "part2" => args.part2 = Some(input.parse()?),
+                    _ => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            "Unknown argument; expected day, year, module, title, tags, parts, \
+                            confirmed, part1 or part2.",
+                        ))
+                    }
+                }
             }
 
-            let year = ints[1].base10_parse::<u16>()?;
-            if year < 1 {
-                return Err(syn::Error::new(
-                    ints[1].span(),
-                    "Year must be greater than 0.",
-                ));
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
             }
+        }
+
+        let (day, year) = match (day, year) {
+            (Some(day), Some(year)) => (day, year),
+            _ => return Err(input.error("Expected day and year like 1, 2022")),
+        };
+        args.day = PuzzleId::check_day(day.base10_parse::<u8>()?)
+            .map_err(|e| syn::Error::new(day.span(), e))?;
+        args.year = PuzzleId::check_year(year.base10_parse::<u16>()?)
+            .map_err(|e| syn::Error::new(year.span(), e))?;
+
+        Ok(args)
+    }
+}
+
+/// Turns a struct name like `NoSpaceLeftOnDevice` into `No Space Left On Device`.
+fn title_from_struct_name(name: &str) -> String {
+    let mut title = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            title.push(' ');
+        }
+        title.push(c);
+    }
+    title
+}
+
+/// A `run`/`run2` function found in a day's module.
+struct SolutionFn {
+    ident: Ident,
+    is_async: bool,
+    /// Where mistakes in its signature are reported
+    span: Span,
+}
+
+impl SolutionFn {
+    /// Looks for the given solution function in a day's module and checks its signature, so that
+    /// mistakes are reported on the attribute rather than somewhere in the generated code.
+    fn find(module: &syn::File, name: &Ident) -> syn::Result<Option<Self>> {
+        let f = module.items.iter().find_map(|item| match item {
+            Item::Fn(f) if f.sig.ident == *name => Some(f),
+            _ => None,
+        });
+        let f = match f {
+            Some(f) => f,
+            None => return Ok(None),
+        };
+
+        if let Visibility::Inherited = f.vis {
+            let sig = &f.sig;
+            return Err(syn::Error::new(
+                Span::call_site(),
+                format!("`{name}` must be public, in `{}`", quote!(#sig)),
+            ));
+        }
+
+        Self::from_signature(&f.sig, Span::call_site()).map(Some)
+    }
+
+    /// Checks that a function can be called by the runner, reporting mistakes at `span`.
+    fn from_signature(sig: &Signature, span: Span) -> syn::Result<Self> {
+        let name = &sig.ident;
+        let error = |msg: &str| syn::Error::new(span, format!("{msg}, in `{}`", quote!(#sig)));
+
+        if sig.inputs.len() != 1 || !Self::is_input_type(&sig.inputs[0]) {
+            return Err(error(&format!(
+                "`{name}` must take the input as its only argument, as &str or &[u8]"
+            )));
+        }
+
+        if let ReturnType::Default = sig.output {
+            return Err(error(&format!(
+                "`{name}` must return the answer, e.g. as anyhow::Result<String>"
+            )));
+        }
+
+        Ok(Self {
+            ident: name.clone(),
+            is_async: sig.asyncness.is_some(),
+            span,
+        })
+    }
+
+    fn is_input_type(arg: &FnArg) -> bool {
+        let ty = match arg {
+            FnArg::Typed(pat) => &*pat.ty,
+            FnArg::Receiver(_) => return false,
+        };
 
-            Ok(Self { day, year })
+        match ty {
+            Type::Reference(r) if r.mutability.is_none() => match &*r.elem {
+                Type::Path(p) => p.path.is_ident("str"),
+                Type::Slice(s) => matches!(&*s.elem, Type::Path(p) if p.path.is_ident("u8")),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn path(&self, module: Option<&proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        match module {
+            Some(module) => quote!(#module::#ident),
+            None => quote!(#ident),
+        }
+    }
+
+    /// Checks that the function returns an answer (once awaited, if it's async), so that another
+    /// return type is reported at the attribute rather than in the generated runner.
+    fn returns_answer(
+        &self,
+        module: Option<&proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
+        let function = self.path(module);
+        let check = match self.is_async {
+            true => quote_spanned! {self.span=>
+                fn returns_answer<A, F: std::future::Future>(_: fn(A) -> F)
+                where
+                    F::Output: aoc_core::IntoAnswer,
+                {
+                }
+            },
+            false => quote_spanned! {self.span=>
+                fn returns_answer<A, R: aoc_core::IntoAnswer>(_: fn(A) -> R) {}
+            },
+        };
+        quote_spanned! {self.span=>
+            const _: fn() = {
+                #check
+                || returns_answer(#function)
+            };
+        }
+    }
+
+    /// Call of this function from the generated runner, with `input: &aoc_core::Input` in scope.
+    fn call(&self, module: Option<&proc_macro2::TokenStream>) -> proc_macro2::TokenStream {
+        let function = self.path(module);
+        let call = quote!(#function(aoc_core::FromInput::from_input(input)?));
+        if self.is_async {
+            quote!(aoc_core::IntoAnswer::into_answer(aoc_core::block_on(#call)))
+        } else {
+            quote!(aoc_core::IntoAnswer::into_answer(#call))
         }
     }
 }
 
+/// Metadata of an exercise that a day's module can define instead of passing it to the attribute:
+/// `TITLE: &str`, `TAGS: &[&str]` and `CONFIRMED: &[u8]`.
+struct ModuleConsts {
+    title: bool,
+    tags: bool,
+    confirmed: bool,
+}
+
+impl ModuleConsts {
+    fn find(module: &syn::File) -> Self {
+        let has_const = |name: &str| {
+            module.items.iter().any(|item| match item {
+                Item::Const(c) => c.ident == name && !matches!(c.vis, Visibility::Inherited),
+                _ => false,
+            })
+        };
+
+        Self {
+            title: has_const("TITLE"),
+            tags: has_const("TAGS"),
+            confirmed: has_const("CONFIRMED"),
+        }
+    }
+}
+
+/// Registers an exercise, implemented by the `aoc_<year>_<day>.rs` module next to the annotated
+/// struct. The module is declared by this attribute, and the exercise is added to
+/// `aoc_core::EXERCISES` at link time, so annotated structs can come in any order.
+///
+/// Solutions can also live in any other module, declared as usual and designated by its path from
+/// the crate root, with functions named as you like.
+///
+/// The title, tags and confirmed parts not given to the attribute are taken from the `TITLE`,
+/// `TAGS` and `CONFIRMED` constants of the module, if it defines them.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// #[advent_of_code(1, 2022)]
+/// pub struct CalorieCounting;
+///
+/// #[advent_of_code(2, 2022, title = "Rock Paper Scissors")]
+/// struct RockPaperScissors;
+///
+/// mod y2022;
+///
+/// #[advent_of_code(day = 3, year = 2022, module = "y2022::day03", part1 = solve_a, part2 = solve_b)]
+/// struct RucksackReorganization;
+///
+/// fn main() -> anyhow::Result<()> {
+///     for exercise in aoc_core::exercises() {
+///         println!("{exercise}");
+///     }
+///     Ok(())
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn advent_of_code(args: TokenStream, ast: TokenStream) -> TokenStream {
     let args_clone = args.clone();
@@ -50,56 +374,207 @@ pub fn advent_of_code(args: TokenStream, ast: TokenStream) -> TokenStream {
     let day = &args.day;
     let year = &args.year;
 
-    if !PathBuf::from(format!("src/aoc_{}_{}.rs", year, day)).exists() {
-        let msg = format!(
-            "aoc_{}_{} module does not exist; create it and implement:\n\t\
-            pub fn run(input: &str) -> anyhow::Result<String> {{}}\n\t\
-            pub fn run2(input: &str) -> anyhow::Result<String> {{}}",
-            year, day
-        );
-        return quote_spanned! {
-            Span::call_site()=>
-            compile_error!(#msg);
+    let part1 = args.part1.clone().unwrap_or_else(|| format_ident!("run"));
+    let part2 = args.part2.clone().unwrap_or_else(|| format_ident!("run2"));
+    let module_name = match &args.module {
+        Some(path) => path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect::<Vec<String>>()
+            .join("::"),
+        None => format!("aoc_{}_{}", year, day),
+    };
+
+    // The module's file is either src/<path>.rs or src/<path>/mod.rs
+    let src_dir =
+        PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).join("src");
+    let module_file = args.module_file();
+    let module_path = [
+        src_dir.join(&module_file),
+        src_dir.join(module_file.with_extension("")).join("mod.rs"),
+    ]
+    .into_iter()
+    .find(|path| path.exists());
+    let module_path = match module_path {
+        Some(path) => path,
+        None => {
+            let msg = format!(
+                "{} module does not exist; create src/{} and implement:\n\t\
+                pub fn {}(input: &str) -> anyhow::Result<String> {{}}\n\t\
+                pub fn {}(input: &str) -> anyhow::Result<String> {{}}\n\
+                (input may also be taken as &[u8], and {} may be left out)",
+                module_name,
+                module_file.display(),
+                part1,
+                part2,
+                part2
+            );
+            return quote_spanned! {
+                Span::call_site()=>
+                compile_error!(#msg);
+            }
+            .into();
         }
-        .into();
-    }
+    };
 
-    proc_append_state(
-        "exercises",
-        &format!("{}:{}:{}", args.year, args.day, ast.ident),
-    )
-    .unwrap();
+    let solutions = std::fs::read_to_string(&module_path)
+        .map_err(|e| syn::Error::new(Span::call_site(), e))
+        .and_then(|content| syn::parse_file(&content))
+        .and_then(|module| {
+            let run = SolutionFn::find(&module, &part1)?.ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    format!("{} module does not define `{}`", module_name, part1),
+                )
+            })?;
+            let run2 = SolutionFn::find(&module, &part2)?;
+            if run2.is_none() && args.part2.is_some() {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    format!("{} module does not define `{}`", module_name, part2),
+                ));
+            }
+            Ok((run, run2, ModuleConsts::find(&module)))
+        });
+    let (run, run2, consts) = match solutions {
+        Ok(solutions) => solutions,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let implemented: Vec<u8> = if run2.is_some() { vec![1, 2] } else { vec![1] };
+    let parts = match &args.parts {
+        Some(parts) => {
+            if let Some(part) = parts.iter().find(|p| !implemented.contains(p)) {
+                let msg = format!("Part {part} is listed in parts, but has no function");
+                return syn::Error::new(Span::call_site(), msg)
+                    .to_compile_error()
+                    .into();
+            }
+            parts.clone()
+        }
+        None => implemented,
+    };
+    if let Some(part) = args.confirmed.iter().find(|p| !parts.contains(p)) {
+        let msg = format!("Part {part} is confirmed, but isn't implemented");
+        return syn::Error::new(Span::call_site(), msg)
+            .to_compile_error()
+            .into();
+    }
 
     let struct_name_str = &ast.ident.to_string();
     let struct_vis = &ast.vis;
-    let usemod_ts = TokenStream::from_str(&format!("use aoc_{}_{}::*;", year, day)).unwrap();
-    let usemod = parse_macro_input!(usemod_ts as syn::ItemUse);
+    let (module_decl, module) = match &args.module {
+        Some(path) => (quote!(), quote!(crate::#path)),
+        None => {
+            // Spelled out, so that the module is found even if the struct comes from an include!
+            let module = format_ident!("aoc_{}_{}", year, day);
+            let file = module_path.to_string_lossy();
+            (quote!(#[path = #file] mod #module;), quote!(#module))
+        }
+    };
+
+    // Arguments of the attribute take precedence over the constants of the module
+    let title = match &args.title {
+        Some(title) => quote!(#title),
+        None if consts.title => quote!(#module::TITLE),
+        None => {
+            let title = title_from_struct_name(struct_name_str);
+            quote!(#title)
+        }
+    };
+    let tags = &args.tags;
+    let tags = match tags.is_empty() && consts.tags {
+        true => quote!(#module::TAGS),
+        false => quote!(&[#(#tags),*]),
+    };
+    let confirmed = &args.confirmed;
+    let confirmed = match confirmed.is_empty() && consts.confirmed {
+        true => quote!(#module::CONFIRMED),
+        false => quote!(&[#(#confirmed),*]),
+    };
+    // The constant of the module is only known once compiled
+    let unimplemented_confirmed =
+        format!("Dec {day}, {year}: the CONFIRMED parts of {module_name} must be implemented");
+    let registration = format_ident!("__AOC_EXERCISE_{}_{}", year, day);
+    let duplicate_guard = format_ident!("__advent_of_code_{}_{}_is_registered_twice", year, day);
+    let run_call = run.call(Some(&module));
+    let mut answer_checks = vec![run.returns_answer(Some(&module))];
+    let run2_fn = match run2 {
+        Some(run2) => {
+            answer_checks.push(run2.returns_answer(Some(&module)));
+            let run2_call = run2.call(Some(&module));
+            quote! {
+                fn run2(&self, input: &aoc_core::Input) -> anyhow::Result<String> {
+                    #run2_call
+                }
+            }
+        }
+        None => quote! {
+            fn run2(&self, _input: &aoc_core::Input) -> anyhow::Result<String> {
+                anyhow::bail!("Part 2 of Dec {}, {} is not implemented", #day, #year)
+            }
+        },
+    };
     TokenStream::from(quote! {
+        #module_decl
+
         #struct_vis struct #struct_name;
 
-        impl aoc_core::AdventOfCodeRunnable for #struct_name {
-            fn matches(&self, day: u8, year: u16) -> bool {
-                day == #day && year == #year
-            }
+        // Registering the same day twice makes this crate-wide name clash at compile time
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #duplicate_guard {
+            () => {};
+        }
+
+        #[aoc_core::linkme::distributed_slice(aoc_core::EXERCISES)]
+        #[linkme(crate = aoc_core::linkme)]
+        static #registration: &'static dyn aoc_core::AdventOfCodeRunnable = &#struct_name;
 
-            fn get_input(&self, oauth_session_id: &str, is_second: bool) -> anyhow::Result<String> {
-                if is_second {
-                    aoc_core::get_input(oauth_session_id, #day, #year, false)
+        #(#answer_checks)*
+
+        const _: () = {
+            const CONFIRMED: &[u8] = #confirmed;
+            let (mut i, parts): (usize, &[u8]) = (0, &[#(#parts),*]);
+            while i < CONFIRMED.len() {
+                let mut j = 0;
+                while j < parts.len() && parts[j] != CONFIRMED[i] {
+                    j += 1;
                 }
-                else {
-                    aoc_core::get_input(oauth_session_id, #day, #year, false)
+                if j == parts.len() {
+                    panic!(#unimplemented_confirmed);
                 }
+                i += 1;
+            }
+        };
+
+        impl aoc_core::AdventOfCodeRunnable for #struct_name {
+            fn id(&self) -> aoc_core::PuzzleId {
+                aoc_core::PuzzleId::new(#year, #day).expect("checked by advent_of_code")
+            }
+
+            fn title(&self) -> &'static str {
+                #title
             }
 
-            fn run(&self, input: &str) -> anyhow::Result<String> {
-                #usemod
-                run(input)
+            fn tags(&self) -> &'static [&'static str] {
+                #tags
             }
 
-            fn run2(&self, input: &str) -> anyhow::Result<String> {
-                #usemod
-                run2(input)
+            fn is_implemented(&self, part: u8) -> bool {
+                [#(#parts),*].contains(&part)
             }
+
+            fn is_confirmed(&self, part: u8) -> bool {
+                (#confirmed).contains(&part)
+            }
+
+            fn run(&self, input: &aoc_core::Input) -> anyhow::Result<String> {
+                #run_call
+            }
+
+            #run2_fn
         }
 
         impl std::fmt::Display for #struct_name {
@@ -110,62 +585,328 @@ pub fn advent_of_code(args: TokenStream, ast: TokenStream) -> TokenStream {
     })
 }
 
-/// This MUST come AFTER you have defined your implemented exercises.
+struct SolverArgs {
+    year: u16,
+    day: u8,
+    part: u8,
+    variant: LitStr,
+}
+
+impl Parse for SolverArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut year = None;
+        let mut day = None;
+        let mut part = None;
+        let mut variant = None;
+
+        // Year and day as the first positional arguments like 2022, 7, or named, then the part
+        // and the name of the variant
+        let mut n_positional = 0;
+        let mut seen = SeenArgs::default();
+        while !input.is_empty() {
+            if input.peek(LitInt) {
+                let int: LitInt = input.parse()?;
+                match n_positional {
+                    0 => {
+                        seen.positional("year", int.span())?;
+                        year = Some(int);
+                    }
+                    1 => {
+                        seen.positional("day", int.span())?;
+                        day = Some(int);
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            int.span(),
+                            "Expected year and day like 2022, 7",
+                        ))
+                    }
+                }
+                n_positional += 1;
+            } else {
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                seen.named(&key)?;
+                match key.to_string().as_str() {
+                    "year" => year = Some(input.parse()?),
+                    "day" => day = Some(input.parse()?),
+                    "part" => part = Some(input.parse::<LitInt>()?),
+                    "variant" => variant = Some(input.parse::<LitStr>()?),
+                    _ => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            "Unknown argument; expected year, day, part or variant.",
+                        ))
+                    }
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let (year, day, part, variant) = match (year, day, part, variant) {
+            (Some(year), Some(day), Some(part), Some(variant)) => (year, day, part, variant),
+            _ => return Err(input.error(
+                "Expected year, day, part and variant like 2022, 7, part = 1, variant = \"arena\"",
+            )),
+        };
+
+        if variant.value() == "default" || variant.value().is_empty() {
+            return Err(syn::Error::new(
+                variant.span(),
+                "The default variant is the exercise's own function; pick another name.",
+            ));
+        }
+
+        Ok(Self {
+            year: PuzzleId::check_year(year.base10_parse::<u16>()?)
+                .map_err(|e| syn::Error::new(year.span(), e))?,
+            day: PuzzleId::check_day(day.base10_parse::<u8>()?)
+                .map_err(|e| syn::Error::new(day.span(), e))?,
+            part: PuzzleId::check_part(part.base10_parse::<u8>()?)
+                .map_err(|e| syn::Error::new(part.span(), e))?,
+            variant,
+        })
+    }
+}
+
+/// Registers a function as an alternative implementation (a variant) of one part of an exercise,
+/// so that several of them can coexist with the exercise's own `run`/`run2`. The function has the
+/// same requirements as those, but doesn't need to be public.
 ///
 /// Example:
 ///
 /// ```rust,ignore
-/// #[advent_of_code(1, 2022)]
-/// pub struct CalorieCounting;
-///
-/// #[advent_of_code(2, 2022)]
-/// struct RockPaperScissors;
-///
-/// declare_exercise_modules!();
-///
-/// int main() -> anyhow::Result<()> {
+/// #[aoc_solver(2022, 7, part = 1, variant = "arena")]
+/// fn run_with_arena(input: &str) -> anyhow::Result<String> {
 ///     ...
 /// }
 /// ```
-#[proc_macro]
-pub fn declare_exercise_modules(_input: TokenStream) -> TokenStream {
-    let mut items = proc_read_state_vec("exercises");
-    items.sort();
+#[proc_macro_attribute]
+pub fn aoc_solver(args: TokenStream, ast: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as SolverArgs);
+    let ast = parse_macro_input!(ast as ItemFn);
 
-    // let mut es: Vec<Box<dyn AdventOfCodeRunnable>> = vec![];
-    let mut mods: Vec<syn::ItemMod> = vec![];
-    for item in items.iter() {
-        let desc: Vec<&str> = item.split(':').collect();
-        let ts = TokenStream::from_str(&format!("mod aoc_{}_{};", desc[0], desc[1])).unwrap();
-        mods.push(parse_macro_input!(ts as syn::ItemMod));
-    }
-    quote!(
-        #(#mods)*
-    )
-    .into()
+    let solver = match SolutionFn::from_signature(&ast.sig, ast.sig.ident.span()) {
+        Ok(solver) => solver,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let (year, day, part) = (args.year, args.day, args.part);
+    let variant = &args.variant;
+    let registration = format_ident!("__AOC_SOLVER_{}", ast.sig.ident.to_string().to_uppercase());
+    let sanitized_variant: String = variant
+        .value()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let duplicate_guard = format_ident!(
+        "__aoc_solver_{}_{}_{}_{}_is_registered_twice",
+        year,
+        day,
+        part,
+        sanitized_variant
+    );
+    let call = solver.call(None);
+    let answer_check = solver.returns_answer(None);
+
+    TokenStream::from(quote! {
+        #ast
+
+        #answer_check
+
+        // Registering the same variant twice makes this crate-wide name clash at compile time
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #duplicate_guard {
+            () => {};
+        }
+
+        #[aoc_core::linkme::distributed_slice(aoc_core::SOLVERS)]
+        #[linkme(crate = aoc_core::linkme)]
+        static #registration: aoc_core::Solver = aoc_core::Solver {
+            year: #year,
+            day: #day,
+            part: #part,
+            variant: #variant,
+            solve: {
+                fn solve(input: &aoc_core::Input) -> anyhow::Result<String> {
+                    #call
+                }
+                solve
+            },
+        };
+    })
 }
 
-#[proc_macro]
-pub fn get_available_exercises(_input: TokenStream) -> TokenStream {
-    let mut items = proc_read_state_vec("exercises");
-    items.sort();
+struct ExampleArgs {
+    name: Option<Ident>,
+    input: LitStr,
+    part1: Option<String>,
+    part2: Option<String>,
+    /// Functions solving the parts, when the exercise doesn't use `run`/`run2`
+    part1_fn: Option<Ident>,
+    part2_fn: Option<Ident>,
+}
 
-    let mut es: Vec<syn::Expr> = vec![];
-    for item in items.iter() {
-        let desc: Vec<&str> = item.split(':').collect();
-        let ts = TokenStream::from_str(desc[2]).unwrap();
-        es.push(parse_macro_input!(ts as syn::Expr));
+impl ExampleArgs {
+    /// Parses an expected answer, given either as a string or as an integer.
+    fn parse_answer(input: ParseStream) -> syn::Result<String> {
+        match input.parse::<Lit>()? {
+            Lit::Str(s) => Ok(s.value()),
+            Lit::Int(i) => Ok(i.base10_digits().to_string()),
+            lit => Err(syn::Error::new(
+                lit.span(),
+                "Expected the answer as a string or an integer",
+            )),
+        }
     }
+}
+
+impl Parse for ExampleArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut name = None;
+        let mut example = None;
+        let mut part1 = None;
+        let mut part2 = None;
+        let mut part1_fn = None;
+        let mut part2_fn = None;
 
-    quote!(
+        let mut seen = SeenArgs::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            seen.named(&key)?;
+            match key.to_string().as_str() {
+                "name" => name = Some(input.parse()?),
+                "input" => example = Some(input.parse()?),
+                "part1" => part1 = Some(Self::parse_answer(input)?),
+                "part2" => part2 = Some(Self::parse_answer(input)?),
+                "part1_fn" => part1_fn = Some(input.parse::<Ident>()?),
+                "part2_fn" => part2_fn = Some(input.parse::<Ident>()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "Unknown argument; expected name, input, part1, part2, part1_fn or \
+                        part2_fn.",
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let example: LitStr = example.ok_or_else(|| input.error("Missing the example's input"))?;
+        if part1.is_none() && part2.is_none() {
+            return Err(syn::Error::new(
+                example.span(),
+                "Expected the answer of part1 and/or part2",
+            ));
+        }
+        for (function, answer, part) in [(&part1_fn, &part1, "part1"), (&part2_fn, &part2, "part2")]
         {
-            use aoc_core::AdventOfCodeRunnable;
-            let mut exercises: Vec<Box<dyn AdventOfCodeRunnable>> = vec![];
-            #(
-                exercises.push(Box::new(#es));
-            )*
-            exercises
-        }
-    )
-    .into()
+            if let (Some(function), None) = (function, answer) {
+                return Err(syn::Error::new(
+                    function.span(),
+                    format!("Expected the answer of {part} to test `{function}` with"),
+                ));
+            }
+        }
+
+        Ok(Self {
+            name,
+            input: example,
+            part1,
+            part2,
+            part1_fn,
+            part2_fn,
+        })
+    }
+}
+
+/// Declares an example from the puzzle's text, in the module implementing the exercise. This
+/// generates tests checking that the module's `run`/`run2` give the expected answers, or the
+/// functions given by `part1_fn`/`part2_fn` for exercises registered with other ones. `async`
+/// functions are run with `aoc_core::block_on`.
+///
+/// A leading newline of the input is ignored, and `name` is only needed to tell several examples
+/// of the same module apart.
+///
+/// Example:
+///
+/// ```rust,ignore
+/// aoc_example! {
+///     input = "
+/// A Y
+/// B X
+/// C Z",
+///     part1 = 15,
+///     part2 = 12,
+/// }
+/// ```
+#[proc_macro]
+pub fn aoc_example(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as ExampleArgs);
+
+    let example = args.input.value();
+    let example = example.strip_prefix('\n').unwrap_or(&example);
+    let module = match &args.name {
+        Some(name) => format_ident!("example_{}", name),
+        None => format_ident!("example"),
+    };
+
+    let module_items = invoking_file();
+    let test = |test_name: &str, function: &Ident, expected: &String| {
+        let test_name = format_ident!("{}", test_name);
+        let mut answer = quote!(super::#function(
+            aoc_core::FromInput::from_input(&input).unwrap(),
+        ));
+        if is_async_fn(module_items.as_ref(), function) {
+            answer = quote!(aoc_core::block_on(#answer));
+        }
+        quote! {
+            #[test]
+            fn #test_name() {
+                let input = aoc_core::Input::from(INPUT);
+                let answer = aoc_core::IntoAnswer::into_answer(#answer);
+                assert_eq!(answer.unwrap(), #expected);
+            }
+        }
+    };
+    let part1_fn = args.part1_fn.unwrap_or_else(|| format_ident!("run"));
+    let part2_fn = args.part2_fn.unwrap_or_else(|| format_ident!("run2"));
+    let part1 = args.part1.as_ref().map(|a| test("part1", &part1_fn, a));
+    let part2 = args.part2.as_ref().map(|a| test("part2", &part2_fn, a));
+
+    TokenStream::from(quote! {
+        #[cfg(test)]
+        mod #module {
+            const INPUT: &str = #example;
+
+            #part1
+            #part2
+        }
+    })
+}
+
+/// The parsed file invoking the macro, unless it can't be read, e.g. when the macro comes from
+/// another one.
+fn invoking_file() -> Option<syn::File> {
+    let path = proc_macro::Span::call_site().local_file()?;
+    syn::parse_file(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Whether the function of the given name in the file is `async`, so that it's called like
+/// `aoc_solver` and `advent_of_code` do. Functions which can't be found are assumed not to be.
+fn is_async_fn(file: Option<&syn::File>, name: &Ident) -> bool {
+    file.is_some_and(|file| {
+        file.items.iter().any(|item| match item {
+            Item::Fn(f) => f.sig.ident == *name && f.sig.asyncness.is_some(),
+            _ => false,
+        })
+    })
 }
@@ -1,6 +1,11 @@
 ﻿use anyhow::bail;
+use macro_support::aoc_example;
 use std::collections::VecDeque;
 
+pub const TITLE: &str = "Calorie Counting";
+pub const TAGS: &[&str] = &["sum", "top-k"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
 pub fn run(input: &str) -> anyhow::Result<String> {
     let mut cur_max: u64 = 0;
 
@@ -34,11 +39,11 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
     }
     record_elf(&mut maxs, cur_sum);
 
-    return if maxs.len() < 3 {
+    if maxs.len() < 3 {
         bail!("Need at least 3 elves in input")
     } else {
         Ok(maxs.iter().sum::<u64>().to_string())
-    };
+    }
 }
 
 fn record_elf(maxs: &mut VecDeque<u64>, new: u64) {
@@ -48,3 +53,23 @@ fn record_elf(maxs: &mut VecDeque<u64>, new: u64) {
         maxs.pop_front();
     }
 }
+
+aoc_example! {
+    input = "
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000",
+    part1 = 24000,
+    part2 = 45000,
+}
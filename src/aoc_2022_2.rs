@@ -1,4 +1,10 @@
-﻿#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+﻿use macro_support::aoc_example;
+
+pub const TITLE: &str = "Rock Paper Scissors";
+pub const TAGS: &[&str] = &["simulation"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Score {
     Loss,
     Draw,
@@ -93,7 +99,7 @@ pub fn run(input: &str) -> anyhow::Result<String> {
 
     for line in input.lines() {
         assert!(line.len() >= 3);
-        let opponent_shape = Shape::from(line.chars().nth(0).unwrap());
+        let opponent_shape = Shape::from(line.chars().next().unwrap());
         let my_shape = Shape::from(line.chars().nth(2).unwrap());
 
         let score = my_shape.against(&opponent_shape);
@@ -108,7 +114,7 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
 
     for line in input.lines() {
         assert!(line.len() >= 3);
-        let opponent_shape = Shape::from(line.chars().nth(0).unwrap());
+        let opponent_shape = Shape::from(line.chars().next().unwrap());
         let target_score = Score::from(line.chars().nth(2).unwrap());
 
         let my_shape = match target_score {
@@ -122,3 +128,12 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
 
     Ok(total_score.to_string())
 }
+
+aoc_example! {
+    input = "
+A Y
+B X
+C Z",
+    part1 = 15,
+    part2 = 12,
+}
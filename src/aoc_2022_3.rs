@@ -1,10 +1,12 @@
-﻿pub struct LetterField(pub u64);
+﻿use aoc_core::bytes::lines;
+use macro_support::aoc_example;
 
-impl Default for LetterField {
-    fn default() -> Self {
-        Self(0)
-    }
-}
+pub const TITLE: &str = "Rucksack Reorganization";
+pub const TAGS: &[&str] = &["bitset"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
+#[derive(Default)]
+pub struct LetterField(pub u64);
 
 impl std::fmt::Display for LetterField {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -16,7 +18,7 @@ impl std::fmt::Display for LetterField {
 }
 
 impl LetterField {
-    pub fn ascii_to_bitfield_index(c: &char) -> usize {
+    pub fn ascii_to_bitfield_index(c: &u8) -> usize {
         if c.is_ascii_uppercase() {
             (*c as usize) - 65 + 26 // 26..52 for A..Z
         } else {
@@ -37,7 +39,7 @@ impl LetterField {
     }
 }
 
-impl<T: Iterator<Item = char>> From<T> for LetterField {
+impl<T: Iterator<Item = u8>> From<T> for LetterField {
     fn from(value: T) -> Self {
         let mut field = Self::default();
         for c in value {
@@ -53,22 +55,22 @@ struct Sack {
     pub cpt2: LetterField,
 }
 
-impl From<&str> for Sack {
-    fn from(content: &str) -> Self {
+impl From<&[u8]> for Sack {
+    fn from(content: &[u8]) -> Self {
         let (cpt1, cpt2) = content.split_at(content.len() / 2);
         assert_eq!(cpt1.len(), cpt2.len());
 
         Self {
-            cpt1: LetterField::from(cpt1.chars()),
-            cpt2: LetterField::from(cpt2.chars()),
+            cpt1: LetterField::from(cpt1.iter().copied()),
+            cpt2: LetterField::from(cpt2.iter().copied()),
         }
     }
 }
 
-pub fn run(input: &str) -> anyhow::Result<String> {
+pub fn run(input: &[u8]) -> anyhow::Result<String> {
     let mut sum: usize = 0;
 
-    for line in input.lines() {
+    for line in lines(input) {
         let sack = Sack::from(line);
         let result = sack.cpt1.combine_with(&sack.cpt2);
 
@@ -89,21 +91,22 @@ struct ThreeSacks {
     pub sack3: LetterField,
 }
 
-impl From<&[&str; 3]> for ThreeSacks {
-    fn from(content: &[&str; 3]) -> Self {
+impl From<&[&[u8]; 3]> for ThreeSacks {
+    fn from(content: &[&[u8]; 3]) -> Self {
         Self {
-            sack1: LetterField::from(content[0].chars()),
-            sack2: LetterField::from(content[1].chars()),
-            sack3: LetterField::from(content[2].chars()),
+            sack1: LetterField::from(content[0].iter().copied()),
+            sack2: LetterField::from(content[1].iter().copied()),
+            sack3: LetterField::from(content[2].iter().copied()),
         }
     }
 }
 
-pub fn run2(input: &str) -> anyhow::Result<String> {
+pub fn run2(input: &[u8]) -> anyhow::Result<String> {
     let mut sum: usize = 0;
 
-    for chunk in input.lines().array_chunks::<3>() {
-        let three_sacks = ThreeSacks::from(&chunk);
+    let lines: Vec<&[u8]> = lines(input).collect();
+    for chunk in lines.as_chunks::<3>().0 {
+        let three_sacks = ThreeSacks::from(chunk);
         let result = three_sacks
             .sack1
             .combine_with(&three_sacks.sack2.combine_with(&three_sacks.sack3));
@@ -115,3 +118,15 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
 
     Ok(sum.to_string())
 }
+
+aoc_example! {
+    input = "
+vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+    part1 = 157,
+    part2 = 70,
+}
@@ -1,4 +1,10 @@
-﻿struct Range<T: Ord + Copy> {
+﻿use macro_support::aoc_example;
+
+pub const TITLE: &str = "Camp Cleanup";
+pub const TAGS: &[&str] = &["ranges"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
+struct Range<T: Ord + Copy> {
     min: T,
     max: T,
 }
@@ -50,3 +56,15 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
     }
     Ok(count.to_string())
 }
+
+aoc_example! {
+    input = "
+2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8",
+    part1 = 2,
+    part2 = 4,
+}
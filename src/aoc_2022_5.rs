@@ -1,9 +1,14 @@
 ﻿use anyhow::bail;
 use lazy_static::lazy_static;
+use macro_support::aoc_example;
 use regex::Regex;
 use smallvec::{smallvec, SmallVec};
 use std::fmt::{Display, Formatter, Write};
 
+pub const TITLE: &str = "Supply Stacks";
+pub const TAGS: &[&str] = &["stacks", "regex"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
 #[derive(Default)]
 struct CrateStack(SmallVec<[char; 512]>);
 
@@ -19,7 +24,7 @@ impl CrateStack {
         let tail = self
             .0
             .as_slice()
-            .take(new_len..)
+            .split_off(new_len..)
             .ok_or(anyhow::format_err!(
                 "Stack has {} items; can't take {n_items} from it",
                 len
@@ -46,7 +51,7 @@ impl Situation {
             bail!("Can't move to the same pile!");
         }
 
-        let [from, to] = self.stacks.get_many_mut([m.from - 1, m.to - 1])?;
+        let [from, to] = self.stacks.get_disjoint_mut([m.from - 1, m.to - 1])?;
         from.move_items_to(m.n_items, to, one_by_one)
     }
 }
@@ -62,7 +67,7 @@ impl Display for Situation {
                     write!(f, "    ").unwrap();
                 }
             });
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -151,3 +156,18 @@ pub fn run(input: &str) -> anyhow::Result<String> {
 pub fn run2(input: &str) -> anyhow::Result<String> {
     run_internal(input, false)
 }
+
+aoc_example! {
+    input = "
+    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2",
+    part1 = "CMZ",
+    part2 = "MCD",
+}
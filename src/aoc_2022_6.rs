@@ -1,4 +1,9 @@
 ﻿use crate::aoc_2022_3::LetterField;
+use macro_support::aoc_example;
+
+pub const TITLE: &str = "Tuning Trouble";
+pub const TAGS: &[&str] = &["bitset", "sliding-window"];
+pub const CONFIRMED: &[u8] = &[1, 2];
 
 fn count_distinct_letters(field: &LetterField) -> usize {
     let mut f = field.0;
@@ -12,16 +17,14 @@ fn count_distinct_letters(field: &LetterField) -> usize {
     n
 }
 
-fn run_internal(input: &str, window_size: usize) -> anyhow::Result<String> {
+fn run_internal(input: &[u8], window_size: usize) -> anyhow::Result<String> {
     let mut index = None;
-    for (i, w) in input
-        .chars()
-        .collect::<Vec<char>>()
-        .windows(window_size)
-        .enumerate()
-    {
-        if count_distinct_letters(&LetterField::from(w.iter().cloned())) == window_size {
-            index = Some((i + window_size, w.to_vec()));
+    for (i, w) in input.windows(window_size).enumerate() {
+        if count_distinct_letters(&LetterField::from(w.iter().copied())) == window_size {
+            index = Some((
+                i + window_size,
+                w.iter().map(|&b| b as char).collect::<Vec<char>>(),
+            ));
             break;
         }
     }
@@ -29,10 +32,16 @@ fn run_internal(input: &str, window_size: usize) -> anyhow::Result<String> {
     Ok(format!("{} ({:?})", r.0, r.1))
 }
 
-pub fn run(input: &str) -> anyhow::Result<String> {
+pub fn run(input: &[u8]) -> anyhow::Result<String> {
     run_internal(input, 4)
 }
 
-pub fn run2(input: &str) -> anyhow::Result<String> {
+pub fn run2(input: &[u8]) -> anyhow::Result<String> {
     run_internal(input, 14)
 }
+
+aoc_example! {
+    input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+    part1 = "7 (['j', 'p', 'q', 'm'])",
+    part2 = "19 (['q', 'm', 'g', 'b', 'l', 'j', 's', 'p', 'h', 'd', 'z', 't', 'n', 'v'])",
+}
@@ -1,8 +1,14 @@
 ﻿use lazy_static::lazy_static;
+use macro_support::{aoc_example, aoc_solver};
 use regex::Regex;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
+pub const TITLE: &str = "No Space Left On Device";
+pub const TAGS: &[&str] = &["tree", "regex"];
+pub const CONFIRMED: &[u8] = &[1, 2];
+
 enum CdArg<'a> {
     Root,
     Parent,
@@ -167,6 +173,7 @@ impl<'a> Iterator for DirWalker<'a> {
     }
 }
 
+#[allow(dead_code)]
 struct FileWalker<'a> {
     folder: Rc<RefCell<Directory<'a>>>,
     cur_file: usize,
@@ -174,6 +181,7 @@ struct FileWalker<'a> {
     cur_folder_walker: Option<Rc<RefCell<FileWalker<'a>>>>,
 }
 
+#[allow(dead_code)]
 impl<'a> FileWalker<'a> {
     fn new(fstree: Rc<RefCell<Directory<'a>>>) -> Self {
         Self {
@@ -190,7 +198,7 @@ impl<'a> FileWalker<'a> {
             // Child folder has finished iterating; destroy sub-walker
             self.cur_folder_walker = None;
         }
-        return ret;
+        ret
     }
 }
 
@@ -289,7 +297,7 @@ fn print_dir(fstree: Rc<RefCell<Directory>>) {
     }
 }
 
-fn build_fstree(input: &str) -> anyhow::Result<Rc<RefCell<Directory>>> {
+fn build_fstree(input: &str) -> anyhow::Result<Rc<RefCell<Directory<'_>>>> {
     let fstree = Rc::new(RefCell::new(Directory {
         size: 0,
         name: "/",
@@ -367,3 +375,125 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
 
     Ok(smallest_dir_size_to_rm.to_string())
 }
+
+// Same as above, but computing directory sizes on a flat arena instead of an Rc<RefCell<..>> tree
+fn arena_dir_sizes(input: &str) -> Vec<usize> {
+    let mut sizes = vec![0]; // Index 0 is "/"
+    let mut children: HashMap<(usize, &str), usize> = HashMap::new();
+    let mut path = vec![0]; // Indices of the directories from "/" to the current one
+
+    for line in input.lines() {
+        match InputLine::from(line) {
+            InputLine::Command(Command::Cd(CdArg::Root)) => path.truncate(1),
+            InputLine::Command(Command::Cd(CdArg::Parent)) => {
+                if path.len() > 1 {
+                    path.pop();
+                }
+            }
+            InputLine::Command(Command::Cd(CdArg::Child(dir_name))) => {
+                let parent = *path.last().unwrap();
+                let next_index = sizes.len();
+                let d = *children.entry((parent, dir_name)).or_insert(next_index);
+                if d == next_index {
+                    sizes.push(0);
+                }
+                path.push(d);
+            }
+            InputLine::File(f) => path.iter().for_each(|&d| sizes[d] += f.size),
+            InputLine::Directory(_) | InputLine::Command(Command::Ls) => {}
+        }
+    }
+
+    sizes
+}
+
+#[aoc_solver(2022, 7, part = 1, variant = "arena")]
+fn run_arena(input: &str) -> usize {
+    arena_dir_sizes(input)
+        .into_iter()
+        .filter(|&size| size <= 100000)
+        .sum()
+}
+
+#[aoc_solver(2022, 7, part = 2, variant = "arena")]
+fn run2_arena(input: &str) -> anyhow::Result<usize> {
+    const TARGET_FREE_SIZE: usize = 30_000_000;
+
+    let sizes = arena_dir_sizes(input);
+    if sizes[0] > 70_000_000 {
+        anyhow::bail!("The files take {} space, more than the whole disk", sizes[0]);
+    }
+    let initial_free_size = 70_000_000 - sizes[0];
+
+    sizes
+        .into_iter()
+        .filter(|&size| initial_free_size + size >= TARGET_FREE_SIZE)
+        .min()
+        .ok_or_else(|| {
+            anyhow::format_err!(
+                "Couldn't find any directory big enough to delete to reach {} free space",
+                TARGET_FREE_SIZE
+            )
+        })
+}
+
+aoc_example! {
+    input = "
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k",
+    part1 = 95437,
+    part2 = 24933642,
+}
+
+aoc_example! {
+    name = arena,
+    input = "
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k",
+    part1 = 95437,
+    part2 = 24933642,
+    part1_fn = run_arena,
+    part2_fn = run2_arena,
+}
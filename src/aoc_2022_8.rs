@@ -1,4 +1,11 @@
-﻿fn count_visible(visible_set: &[u128]) -> usize {
+﻿use aoc_core::bytes::lines;
+use macro_support::aoc_example;
+
+pub const TITLE: &str = "Treetop Tree House";
+pub const TAGS: &[&str] = &["grid", "bitset"];
+pub const CONFIRMED: &[u8] = &[1];
+
+fn count_visible(visible_set: &[u128]) -> usize {
     let mut count = 0;
     for set in visible_set {
         let mut set = *set;
@@ -23,7 +30,7 @@ fn tag_visible(
     visible_set: &mut [u128],
     mut biggest_from_left: u8,
 ) -> u8 {
-    if let Some(first) = row.take_first() {
+    if let Some(first) = row.split_off_first() {
         if *first > biggest_from_left {
             biggest_from_left = *first;
             visible_set[i / 128] |= 1 << (i % 128);
@@ -39,8 +46,8 @@ fn tag_visible(
     }
 }
 
-pub fn run(input: &str) -> anyhow::Result<String> {
-    let lines: Vec<&str> = input.lines().collect();
+pub fn run(input: &[u8]) -> anyhow::Result<String> {
+    let lines: Vec<&[u8]> = lines(input).collect();
     let height = lines.len();
     let width = lines[0].len();
     let mut columns: Vec<Vec<u8>> = vec![vec![0; height]; width];
@@ -48,17 +55,12 @@ pub fn run(input: &str) -> anyhow::Result<String> {
 
     // Count horizontally
     for (i, line) in lines.iter().enumerate() {
-        let line: Vec<u8> = line
-            .chars()
-            .enumerate()
-            .map(|(j, c)| {
-                columns[j][i] = c as u8;
-                c as u8
-            })
-            .collect();
+        for (j, c) in line.iter().enumerate() {
+            columns[j][i] = *c;
+        }
 
         let mut visible_sets = vec![0; 1 + (width - 1) / 128];
-        tag_visible(0, &line, &mut visible_sets, 0);
+        tag_visible(0, line, &mut visible_sets, 0);
 
         // ugh, transpose the visible bits so that we can use them when we'll count vertically...
         for j in 0..width {
@@ -74,14 +76,22 @@ pub fn run(input: &str) -> anyhow::Result<String> {
 
     // Parse vertically and update count, column by column
     for (j, col) in columns.iter().enumerate() {
-        tag_visible(0, &col, &mut columns_visible_sets[j], 0);
+        tag_visible(0, col, &mut columns_visible_sets[j], 0);
         count += count_visible(&columns_visible_sets[j]);
     }
 
     Ok(count.to_string())
 }
 
-pub fn run2(_input: &str) -> anyhow::Result<String> {
-    Ok("So in ex1, I actually had to count for each tree in all direction if they reached the end? \
-    Then reuse the same function for part 2 from any tree? Pffff.".to_string())
+// No run2 yet: so in ex1, I actually had to count for each tree in all direction if they reached
+// the end? Then reuse the same function for part 2 from any tree? Pffff.
+
+aoc_example! {
+    input = "
+30373
+25512
+65332
+33549
+35390",
+    part1 = 21,
 }
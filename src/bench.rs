@@ -0,0 +1,334 @@
+use crate::guard::{self, PartError, SolveFn};
+use crate::mem;
+use anyhow::{Context, Result};
+use aoc_core::{AdventOfCodeRunnable, Input, PuzzleId};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Summary of the durations measured over the iterations of a benchmark.
+#[derive(Copy, Clone)]
+pub struct Stats {
+    pub min: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let n = samples.len();
+        let mean = samples.iter().sum::<Duration>() / n as u32;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        Self {
+            min: samples[0],
+            mean,
+            median: match n % 2 {
+                0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+                _ => samples[n / 2],
+            },
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+pub struct Options {
+    /// Runs before the measures, to warm up caches and the branch predictor
+    pub warmup: u32,
+    pub iterations: u32,
+    /// Of each run of a part
+    pub timeout: Option<Duration>,
+}
+
+/// Benchmark of one part of an exercise.
+pub struct PartBench {
+    /// The puzzle, with the part
+    pub id: PuzzleId,
+    pub exercise: String,
+    /// The solution measured: the exercise's own one, not one of its variants
+    pub variant: &'static str,
+    /// Mapping the cached input, shared by both parts
+    pub load: Stats,
+    /// Or the failure of the first run which failed
+    pub measures: Result<Measures, PartError>,
+}
+
+/// What is measured of a part which gave its answer on every run.
+pub struct Measures {
+    pub answer: String,
+    pub solve: Stats,
+    /// Heap used by the last run
+    pub mem: mem::Usage,
+}
+
+/// Measures the time taken to load the input of the exercise, and to solve each of its parts. The
+/// parts are run like `run` does, so their failures are reported with their benchmarks.
+pub fn bench(
+    ex: &'static dyn AdventOfCodeRunnable,
+    session_id: &str,
+    options: &Options,
+) -> Result<Vec<PartBench>> {
+    // Makes sure the input is cached, so that only loading it is measured
+    ex.get_input(session_id, false)?;
+    let path = aoc_core::cached_input_path(&ex.id());
+    let load = Stats::new(measure(options, || Input::open(&path).map(|_| ()))?);
+
+    let input = Arc::new(Input::open(&path)?);
+    let mut benches = vec![];
+    for part in [1, 2].into_iter().filter(|p| ex.is_implemented(*p)) {
+        benches.push(PartBench {
+            id: ex.id().with_part(part)?,
+            exercise: ex.to_string(),
+            variant: "default",
+            load,
+            measures: measure_part(&guard::solution(ex, part), &input, options),
+        });
+    }
+    Ok(benches)
+}
+
+/// Runs the part for the warmup, then measures its runs, until one fails.
+fn measure_part(
+    solve: &SolveFn,
+    input: &Arc<Input>,
+    options: &Options,
+) -> Result<Measures, PartError> {
+    let mut samples = Vec::with_capacity(options.iterations as usize);
+    let mut last = None;
+    for i in 0..options.warmup + options.iterations.max(1) {
+        let run = guard::run(solve, input, options.timeout);
+        let answer = run.answer?;
+        if i >= options.warmup {
+            samples.push(run.elapsed);
+        }
+        last = Some((answer, run.mem));
+    }
+
+    let (answer, mem) = last.expect("the part is run at least once");
+    Ok(Measures {
+        answer,
+        solve: Stats::new(samples),
+        mem,
+    })
+}
+
+fn measure(options: &Options, mut f: impl FnMut() -> Result<()>) -> Result<Vec<Duration>> {
+    for _ in 0..options.warmup {
+        f()?;
+    }
+
+    let mut samples = Vec::with_capacity(options.iterations as usize);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+    Ok(samples)
+}
+
+/// Median solve times of a previous benchmark, saved as lines like `2022/7/1 95437` (in ns).
+#[derive(Default)]
+pub struct Baseline(BTreeMap<PuzzleId, Duration>);
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the baseline {}", path.display()))?;
+
+        let mut baseline = Self::default();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let (id, nanos) = line
+                .split_once(' ')
+                .with_context(|| format!("Invalid baseline line {line:?}"))?;
+            let nanos: u64 = nanos
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid baseline line {line:?}"))?;
+            baseline.0.insert(id.parse()?, Duration::from_nanos(nanos));
+        }
+        Ok(baseline)
+    }
+
+    /// Saves the results in the baseline file, keeping the other parts it already has.
+    pub fn save(path: &Path, benches: &[PartBench]) -> Result<()> {
+        let mut baseline = match path.exists() {
+            true => Self::load(path)?,
+            false => Self::default(),
+        };
+        for b in benches.iter() {
+            if let Ok(measures) = &b.measures {
+                baseline.0.insert(b.id, measures.solve.median);
+            }
+        }
+
+        let content: String = baseline
+            .0
+            .iter()
+            .map(|(id, median)| format!("{id} {}\n", median.as_nanos()))
+            .collect();
+        fs::write(path, content)
+            .with_context(|| format!("Could not write the baseline {}", path.display()))
+    }
+
+    /// Median solve time of the part (with its id), if known.
+    pub fn median(&self, id: &PuzzleId) -> Option<Duration> {
+        self.0.get(id).copied()
+    }
+
+    /// Relative change of the median solve time of the part since the baseline, in percent.
+    pub fn change(&self, bench: &PartBench) -> Option<f64> {
+        let median = bench.measures.as_ref().ok()?.solve.median.as_secs_f64();
+        let base = self.median(&bench.id)?.as_secs_f64();
+        if base == 0. {
+            return None;
+        }
+        Some((median - base) / base * 100.)
+    }
+
+    /// Whether the part got slower than the baseline by more than the threshold, in percent.
+    pub fn regressed(&self, bench: &PartBench, threshold: f64) -> bool {
+        self.change(bench).is_some_and(|c| c > threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().map(|&s| Duration::from_micros(s)).collect()
+    }
+
+    fn part_bench(id: &str, median: Duration) -> PartBench {
+        let stats = Stats::new(vec![median]);
+        PartBench {
+            id: id.parse().unwrap(),
+            exercise: String::new(),
+            variant: "default",
+            load: stats,
+            measures: Ok(Measures {
+                answer: String::new(),
+                solve: stats,
+                mem: mem::Usage::default(),
+            }),
+        }
+    }
+
+    fn options(timeout: Option<Duration>) -> Options {
+        Options {
+            warmup: 2,
+            iterations: 5,
+            timeout,
+        }
+    }
+
+    #[test]
+    fn stats_of_odd_samples() {
+        let stats = Stats::new(micros(&[9, 1, 5]));
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.median, Duration::from_micros(5));
+        assert_eq!(stats.mean, Duration::from_micros(5));
+        // Population standard deviation: sqrt((16 + 16 + 0) / 3)
+        assert_eq!(stats.stddev.as_nanos(), 3266);
+    }
+
+    #[test]
+    fn stats_of_even_samples() {
+        let stats = Stats::new(micros(&[8, 2, 4, 100]));
+        assert_eq!(stats.min, Duration::from_micros(2));
+        // Between the two middle samples
+        assert_eq!(stats.median, Duration::from_micros(6));
+        assert_eq!(stats.mean, Duration::from_nanos(28_500));
+
+        let stats = Stats::new(micros(&[3]));
+        assert_eq!((stats.min, stats.median), (stats.mean, stats.mean));
+        assert_eq!(stats.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc-baseline-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert!(Baseline::load(&path).is_err());
+
+        let day1 = part_bench("2022/1/1", Duration::from_nanos(1500));
+        Baseline::save(
+            &path,
+            &[day1, part_bench("2022/7/2", Duration::from_micros(2))],
+        )
+        .unwrap();
+        // Other parts are kept, and saved ones are replaced
+        Baseline::save(&path, &[part_bench("2022/1/1", Duration::from_nanos(1200))]).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let baseline = Baseline::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(content, "2022/1/1 1200\n2022/7/2 2000\n");
+        let baseline = baseline.unwrap();
+        assert_eq!(
+            baseline.median(&"2022/7/2".parse().unwrap()),
+            Some(Duration::from_micros(2))
+        );
+        assert_eq!(baseline.median(&"2022/7/1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn regressions_beyond_the_threshold() {
+        let mut baseline = Baseline::default();
+        baseline
+            .0
+            .insert("2022/1/1".parse().unwrap(), Duration::from_micros(100));
+
+        let slower = |micros| part_bench("2022/1/1", Duration::from_micros(micros));
+        assert_eq!(baseline.change(&slower(110)), Some(10.));
+        // Only slowdowns beyond the threshold count
+        assert!(!baseline.regressed(&slower(110), 10.));
+        assert!(!baseline.regressed(&slower(109), 10.));
+        assert!(baseline.regressed(&slower(111), 10.));
+        assert!(!baseline.regressed(&slower(50), 10.));
+        // Parts missing from the baseline can't regress
+        assert!(!baseline.regressed(&part_bench("2022/2/1", Duration::MAX), 10.));
+    }
+
+    #[test]
+    fn measures_parts() {
+        let runs = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = runs.clone();
+        let solve: SolveFn = Arc::new(move |input| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(input.as_bytes().len().to_string())
+        });
+        let measures = measure_part(&solve, &Arc::new(Input::from("abc")), &options(None));
+
+        let measures = measures.unwrap();
+        assert_eq!(measures.answer, "3");
+        assert_eq!(runs.load(std::sync::atomic::Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn reports_failed_parts() {
+        let input = Arc::new(Input::from("Q"));
+        let panics: SolveFn = Arc::new(|input| panic!("Unknown shape: {}", input.as_str()?));
+        let e = measure_part(&panics, &input, &options(None)).err().unwrap();
+        assert_eq!(e.status(), "panicked");
+        assert!(e.to_string().ends_with("Unknown shape: Q"), "{e}");
+
+        let hangs: SolveFn = Arc::new(|_| {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(String::new())
+        });
+        let timeout = Some(Duration::from_millis(10));
+        let e = measure_part(&hangs, &input, &options(timeout))
+            .err()
+            .unwrap();
+        assert_eq!(e.status(), "timeout");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::output::Format;
+use anyhow::{Context, Result};
+use aoc_core::PuzzleId;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub static FILE_NAME: &str = "aoc.toml";
+
+/// Defaults of the commands, read from the `aoc.toml` of the working directory (e.g. shared by a
+/// team) and from the user's one (e.g. with their session), the former taking precedence field
+/// by field. Relative paths are relative to the file setting them.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Year of the exercises when only their days are given
+    pub year: Option<u16>,
+    /// OAUTH session ID (cookie) for adventofcode.com
+    pub session: Option<String>,
+    /// Profile whose session to use instead
+    pub profile: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+    pub cache_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    /// Never download anything
+    pub offline: Option<bool>,
+    /// User-Agent of the requests to adventofcode.com, which asks for a way to contact you
+    pub user_agent: Option<String>,
+    /// SQLite database of the executions of `run` and `bench`
+    pub history: Option<PathBuf>,
+}
+
+/// Session of one of the accounts on adventofcode.com.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub session: String,
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        let project = Self::read(Path::new(FILE_NAME))?;
+        let user = match Self::user_path() {
+            Some(path) => Self::read(&path)?,
+            None => None,
+        };
+
+        let config = match (project, user) {
+            (Some(project), Some(user)) => project.or(user),
+            (project, user) => project.or(user).unwrap_or_default(),
+        };
+        if let Some(year) = config.year {
+            PuzzleId::check_year(year).context("Invalid default year in aoc.toml")?;
+        }
+        Ok(config)
+    }
+
+    fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        Ok(Some(match path.parent() {
+            Some(dir) => config.relative_to(dir),
+            None => config,
+        }))
+    }
+
+    /// This config, with its relative paths made relative to the given directory instead.
+    fn relative_to(self, dir: &Path) -> Self {
+        let resolve = |path: Option<PathBuf>| path.map(|p| dir.join(p));
+        Self {
+            cache_dir: resolve(self.cache_dir),
+            history: resolve(self.history),
+            ..self
+        }
+    }
+
+    /// `aoc/aoc.toml` in the configuration directory of the user.
+    fn user_path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None if cfg!(windows) => PathBuf::from(env::var_os("APPDATA")?),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("aoc").join(FILE_NAME))
+    }
+
+    /// This config, completed by the fallback one.
+    fn or(self, fallback: Self) -> Self {
+        let mut profiles = fallback.profiles;
+        profiles.extend(self.profiles);
+        // Both choose the session, so a profile of the fallback can't override a session of this
+        // config
+        let (session, profile) = match self.session.is_some() || self.profile.is_some() {
+            true => (self.session, self.profile),
+            false => (fallback.session, fallback.profile),
+        };
+        Self {
+            year: self.year.or(fallback.year),
+            session,
+            profile,
+            profiles,
+            cache_dir: self.cache_dir.or(fallback.cache_dir),
+            format: self.format.or(fallback.format),
+            offline: self.offline.or(fallback.offline),
+            user_agent: self.user_agent.or(fallback.user_agent),
+            history: self.history.or(fallback.history),
+        }
+    }
+
+    /// The session of the given profile, or else of the default one, or else the session of the
+    /// config.
+    pub fn session(&self, profile: Option<&str>) -> Result<Option<&str>> {
+        match profile.or(self.profile.as_deref()) {
+            Some(name) => match self.profiles.get(name) {
+                Some(profile) => Ok(Some(&profile.session)),
+                None => anyhow::bail!("No profile {name:?} in aoc.toml"),
+            },
+            None => Ok(self.session.as_deref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn project_fields_win() {
+        let project =
+            config("year = 2022\nformat = \"csv\"\n[profiles.work]\nsession = \"project-work\"");
+        let user = config(
+            "year = 2021\noffline = true\nuser_agent = \"me\"\n\
+            [profiles.work]\nsession = \"user-work\"\n[profiles.home]\nsession = \"home\"",
+        );
+        let config = project.or(user);
+        assert_eq!(config.year, Some(2022));
+        assert!(config.format == Some(Format::Csv));
+        assert_eq!(config.offline, Some(true));
+        assert_eq!(config.user_agent.as_deref(), Some("me"));
+        assert_eq!(config.session(Some("work")).unwrap(), Some("project-work"));
+        assert_eq!(config.session(Some("home")).unwrap(), Some("home"));
+        assert!(config.session(Some("other")).is_err());
+    }
+
+    #[test]
+    fn project_session_beats_user_profile() {
+        let user = || config("profile = \"home\"\n[profiles.home]\nsession = \"home\"");
+        let config = config("session = \"project\"").or(user());
+        assert_eq!(config.session(None).unwrap(), Some("project"));
+        // Unless the profile is chosen explicitly
+        assert_eq!(config.session(Some("home")).unwrap(), Some("home"));
+
+        let project = Config::default().or(user());
+        assert_eq!(project.session(None).unwrap(), Some("home"));
+    }
+
+    #[test]
+    fn project_profile_beats_user_session() {
+        let project = config("profile = \"work\"\n[profiles.work]\nsession = \"work\"");
+        let config = project.or(config("session = \"user\""));
+        assert_eq!(config.session(None).unwrap(), Some("work"));
+    }
+
+    #[test]
+    fn paths_relative_to_their_file() {
+        let dir = Path::new("/home/me/.config/aoc");
+        let user =
+            config("cache_dir = \"inputs\"\nhistory = \"/var/aoc/history.db\"").relative_to(dir);
+        assert_eq!(user.cache_dir, Some(dir.join("inputs")));
+        assert_eq!(user.history, Some(PathBuf::from("/var/aoc/history.db")));
+
+        // The file of the project is in the working directory
+        let project =
+            config("cache_dir = \"inputs\"").relative_to(Path::new(FILE_NAME).parent().unwrap());
+        assert_eq!(project.cache_dir, Some(PathBuf::from("inputs")));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::mem;
+use anyhow::{Context, Result};
+use aoc_core::{AdventOfCodeRunnable, Input};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Stack of the threads running parts with a timeout, as large as the one of the main thread so
+/// that deep recursions behave the same.
+pub const STACK_SIZE: usize = 8 << 20;
+
+/// A solution of a part, which can be moved to the thread running it.
+pub type SolveFn = Arc<dyn Fn(&Input) -> Result<String> + Send + Sync>;
+
+/// The solution of the part registered with `#[advent_of_code]`.
+pub fn solution(ex: &'static dyn AdventOfCodeRunnable, part: u8) -> SolveFn {
+    match part {
+        2 => Arc::new(move |input| ex.run2(input)),
+        _ => Arc::new(move |input| ex.run(input)),
+    }
+}
+
+/// Why a part gave no answer.
+#[derive(Debug)]
+pub enum PartError {
+    Failed(anyhow::Error),
+    Panicked {
+        message: String,
+        /// Like `src/aoc_2022_2.rs:21:18`
+        location: Option<String>,
+    },
+    TimedOut(Duration),
+    /// Killed for using more resident memory than this many bytes, see [`crate::isolate`]
+    OutOfMemory(usize),
+    /// The process of the part died without an answer, see [`crate::isolate`]
+    Crashed(String),
+    /// Failure reported by the process of the part, see [`crate::isolate`]
+    Reported {
+        status: String,
+        message: String,
+    },
+}
+
+impl PartError {
+    /// Name of the failure in the status column of the results.
+    pub fn status(&self) -> &str {
+        match self {
+            PartError::Failed(_) => "error",
+            PartError::Panicked { .. } => "panicked",
+            PartError::TimedOut(_) => "timeout",
+            PartError::OutOfMemory(_) => "memory",
+            PartError::Crashed(_) => "crashed",
+            PartError::Reported { status, .. } => status,
+        }
+    }
+}
+
+impl Display for PartError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartError::Failed(e) => write!(f, "{e}"),
+            PartError::Panicked {
+                message,
+                location: Some(location),
+            } => write!(f, "panicked at {location}: {message}"),
+            PartError::Panicked { message, .. } => write!(f, "panicked: {message}"),
+            PartError::TimedOut(timeout) => write!(f, "timed out after {timeout:?}"),
+            PartError::OutOfMemory(limit) => {
+                write!(
+                    f,
+                    "used more than {} of memory",
+                    crate::output::human_bytes(*limit)
+                )
+            }
+            PartError::Crashed(cause) => write!(f, "crashed: {cause}"),
+            PartError::Reported { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for PartError {}
+
+/// Outcome of a part.
+pub struct Run {
+    pub answer: Result<String, PartError>,
+    pub elapsed: Duration,
+    pub mem: mem::Usage,
+}
+
+impl Run {
+    /// A part which couldn't run, or didn't finish.
+    pub fn failed(e: PartError) -> Self {
+        Self {
+            answer: Err(e),
+            elapsed: Duration::ZERO,
+            mem: mem::Usage::default(),
+        }
+    }
+}
+
+/// Runs a part, turning its panics into errors. With a timeout, the part runs in a thread of its
+/// own, and is given up on after that time; it then keeps running in the background until the
+/// runner exits, skewing the time and memory measured for the next parts.
+pub fn run(solve: &SolveFn, input: &Arc<Input>, timeout: Option<Duration>) -> Run {
+    install_panic_hook();
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return run_here(solve, input),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let (solve, input) = (solve.clone(), input.clone());
+    let spawned = thread::Builder::new()
+        .name("solution".to_string())
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let _ = tx.send(run_here(&solve, &input));
+        })
+        .context("Could not start the thread of the part");
+
+    let answer = match spawned.map(|_| rx.recv_timeout(timeout)) {
+        Ok(Ok(run)) => return run,
+        Ok(Err(RecvTimeoutError::Timeout)) => Err(PartError::TimedOut(timeout)),
+        Ok(Err(RecvTimeoutError::Disconnected)) => Err(PartError::Failed(anyhow::anyhow!(
+            "The thread of the part died"
+        ))),
+        Err(e) => Err(PartError::Failed(e)),
+    };
+    Run {
+        answer,
+        elapsed: timeout,
+        mem: mem::Usage::default(),
+    }
+}
+
+fn run_here(solve: &SolveFn, input: &Input) -> Run {
+    let start = Instant::now();
+    let (result, mem) = mem::measure(|| {
+        CATCHING.set(true);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solve(input)));
+        CATCHING.set(false);
+        result
+    });
+    let elapsed = start.elapsed();
+
+    let answer = match result {
+        Ok(Ok(answer)) => Ok(answer),
+        Ok(Err(e)) => Err(PartError::Failed(e)),
+        Err(payload) => Err(PartError::Panicked {
+            message: panic_message(payload),
+            location: LOCATION.take(),
+        }),
+    };
+    Run {
+        answer,
+        elapsed,
+        mem,
+    }
+}
+
+thread_local! {
+    /// Whether the thread is running a part, whose panics are caught
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// Where the last caught panic happened
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Keeps the location of the panics of the parts, instead of printing them like other panics.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| match CATCHING.get() {
+            true => LOCATION.set(info.location().map(|l| {
+                // `#[advent_of_code]` declares the day modules with their absolute path
+                let file = Path::new(l.file());
+                let file = file
+                    .strip_prefix(env!("CARGO_MANIFEST_DIR"))
+                    .unwrap_or(file);
+                format!("{}:{}:{}", file.display(), l.line(), l.column())
+            })),
+            false => default_hook(info),
+        }));
+    });
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "unknown cause".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(solve: SolveFn, timeout: Option<Duration>) -> Run {
+        run(&solve, &Arc::new(Input::from("Q")), timeout)
+    }
+
+    #[test]
+    fn answers() {
+        let run = run_with(Arc::new(|input| Ok(input.as_str()?.to_lowercase())), None);
+        assert_eq!(run.answer.unwrap(), "q");
+    }
+
+    #[test]
+    fn panics_with_their_location() {
+        let line = line!() + 1;
+        let solve: SolveFn = Arc::new(|input| panic!("Unknown shape: {}", input.as_str()?));
+        for timeout in [None, Some(Duration::from_secs(10))] {
+            let e = run_with(solve.clone(), timeout).answer.unwrap_err();
+            assert_eq!(e.status(), "panicked");
+            match &e {
+                PartError::Panicked { message, location } => {
+                    assert_eq!(message, "Unknown shape: Q");
+                    let location = location.as_deref().unwrap();
+                    assert!(
+                        location.starts_with(&format!("src/guard.rs:{line}:")),
+                        "{location}"
+                    );
+                }
+                e => panic!("Unexpected error {e:?}"),
+            }
+            assert!(
+                e.to_string().starts_with("panicked at src/guard.rs:"),
+                "{e}"
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let run = run_with(Arc::new(|_| anyhow::bail!("No marker")), None);
+        let e = run.answer.unwrap_err();
+        assert_eq!((e.status(), e.to_string().as_str()), ("error", "No marker"));
+    }
+
+    #[test]
+    fn times_out() {
+        let solve: SolveFn = Arc::new(|_| {
+            thread::sleep(Duration::from_secs(2));
+            Ok(String::new())
+        });
+        let run = run_with(solve, Some(Duration::from_millis(20)));
+        let e = run.answer.unwrap_err();
+        assert_eq!(e.status(), "timeout");
+        assert_eq!(e.to_string(), "timed out after 20ms");
+        assert_eq!(run.elapsed, Duration::from_millis(20));
+    }
+
+    #[test]
+    fn panic_messages() {
+        assert_eq!(panic_message(Box::new("static")), "static");
+        assert_eq!(
+            panic_message(Box::new(String::from("formatted"))),
+            "formatted"
+        );
+        assert_eq!(panic_message(Box::new(42)), "unknown cause");
+    }
+}
@@ -0,0 +1,331 @@
+use crate::output::{Cell, Column, Format, Table};
+use crate::{bench, guard, isolate};
+use anyhow::{Context, Result};
+use aoc_core::PuzzleId;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Name of the history when the config doesn't set one, next to the cache of the inputs.
+pub static FILE_NAME: &str = "history.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY,
+        timestamp INTEGER NOT NULL,
+        git_commit TEXT,
+        command TEXT NOT NULL,
+        year INTEGER NOT NULL,
+        day INTEGER NOT NULL,
+        part INTEGER NOT NULL,
+        variant TEXT NOT NULL,
+        status TEXT NOT NULL,
+        answer TEXT,
+        duration_ns INTEGER,
+        min_ns INTEGER,
+        mean_ns INTEGER,
+        stddev_ns INTEGER,
+        peak_heap_bytes INTEGER,
+        allocated_bytes INTEGER,
+        allocations INTEGER,
+        wall_ns INTEGER,
+        cpu_ns INTEGER,
+        max_rss_bytes INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS runs_by_day ON runs (year, day, part, variant);
+";
+
+/// One execution of a part by `run` or `bench`. Only the solve time is known for `run`, which is
+/// the median one for `bench`.
+#[derive(Default)]
+pub struct Record<'a> {
+    pub variant: &'a str,
+    pub status: &'a str,
+    pub answer: Option<&'a str>,
+    pub duration: Option<Duration>,
+    pub stats: Option<bench::Stats>,
+    pub mem: Option<crate::mem::Usage>,
+    pub process: Option<isolate::Resources>,
+}
+
+impl<'a> Record<'a> {
+    pub fn of_run(
+        variant: &'a str,
+        run: &'a guard::Run,
+        process: Option<isolate::Resources>,
+    ) -> Self {
+        match &run.answer {
+            Ok(answer) => Self {
+                variant,
+                status: "ok",
+                answer: Some(answer),
+                duration: Some(run.elapsed),
+                mem: Some(run.mem),
+                process,
+                ..Default::default()
+            },
+            Err(e) => Self {
+                variant,
+                status: e.status(),
+                process,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn of_bench(bench: &'a bench::PartBench) -> Self {
+        match &bench.measures {
+            Ok(measures) => Self {
+                variant: bench.variant,
+                status: "ok",
+                answer: Some(&measures.answer),
+                duration: Some(measures.solve.median),
+                stats: Some(measures.solve),
+                mem: Some(measures.mem),
+                ..Default::default()
+            },
+            Err(e) => Self {
+                variant: bench.variant,
+                status: e.status(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Local SQLite database of every execution of the parts, to follow their times and answers across
+/// commits.
+pub struct History(Connection);
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("Could not open the history {}", path.display()))?;
+        connection
+            .execute_batch(SCHEMA)
+            .with_context(|| format!("Invalid history {}", path.display()))?;
+        Ok(Self(connection))
+    }
+
+    /// Records the execution of the part (given by `id`) by the command.
+    pub fn record(&self, command: &str, id: &PuzzleId, record: &Record) -> Result<()> {
+        let nanos = |d: Duration| d.as_nanos() as i64;
+        self.0.execute(
+            "INSERT INTO runs (timestamp, git_commit, command, year, day, part, variant, status, \
+                answer, duration_ns, min_ns, mean_ns, stddev_ns, peak_heap_bytes, allocated_bytes, \
+                allocations, wall_ns, cpu_ns, max_rss_bytes) \
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, \
+                ?19)",
+            params![
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+                git_commit(),
+                command,
+                id.year(),
+                id.day(),
+                id.part(),
+                record.variant,
+                record.status,
+                record.answer,
+                record.duration.map(nanos),
+                record.stats.map(|s| nanos(s.min)),
+                record.stats.map(|s| nanos(s.mean)),
+                record.stats.map(|s| nanos(s.stddev)),
+                record.mem.map(|m| m.peak as i64),
+                record.mem.map(|m| m.allocated as i64),
+                record.mem.map(|m| m.allocations as i64),
+                record.process.map(|p| nanos(p.wall)),
+                record.process.map(|p| nanos(p.cpu)),
+                record.process.map(|p| p.max_rss as i64),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Prints the executions of the parts of a day, oldest first, with the change of their time
+    /// since the previous execution of the same part, variant and command, and flags the answers
+    /// that changed.
+    pub fn print(&self, id: &PuzzleId, limit: usize, format: Format) -> Result<()> {
+        let (table, changes) = self.executions(id, limit)?;
+        table.print(format);
+        if format.is_human() && !changes.is_empty() {
+            println!("\n{}", changes.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// The table of [`print`](Self::print), and the descriptions of the changes of answers.
+    fn executions(&self, id: &PuzzleId, limit: usize) -> Result<(Table, Vec<String>)> {
+        let mut statement = self.0.prepare(
+            "SELECT * FROM (
+                SELECT strftime('%Y-%m-%d %H:%M:%S', timestamp, 'unixepoch'), git_commit, command,
+                    part, variant, status, answer, duration_ns, peak_heap_bytes,
+                    LAG(duration_ns) OVER same_part,
+                    LAG(answer) OVER (
+                        PARTITION BY part, variant, answer IS NULL ORDER BY timestamp, id
+                    ),
+                    timestamp, id
+                FROM runs
+                WHERE year = ?1 AND day = ?2 AND (?3 IS NULL OR part = ?3)
+                WINDOW same_part AS (PARTITION BY part, variant, command ORDER BY timestamp, id)
+                ORDER BY timestamp DESC, id DESC
+                LIMIT ?4
+            ) ORDER BY timestamp, id",
+        )?;
+        let mut rows = statement.query(params![id.year(), id.day(), id.part(), limit as i64])?;
+
+        let mut table = Table::new(vec![
+            Column::new("time", "When (UTC)"),
+            Column::new("git_commit", "Commit"),
+            Column::new("command", "Command"),
+            Column::new("part", "Part"),
+            Column::new("variant", "Variant"),
+            Column::new("status", "Status"),
+            Column::new("answer", "Answer"),
+            Column::new("answer_changed", "Changed"),
+            Column::new("duration_ns", "Time"),
+            Column::new("change_percent", "Change %"),
+            Column::new("peak_heap_bytes", "Peak heap"),
+        ]);
+        let mut changes = vec![];
+        while let Some(row) = rows.next()? {
+            let (time, commit): (String, Option<String>) = (row.get(0)?, row.get(1)?);
+            let (part, variant): (i64, String) = (row.get(3)?, row.get(4)?);
+            let answer: Option<String> = row.get(6)?;
+            let duration: Option<i64> = row.get(7)?;
+            let (previous_duration, previous_answer): (Option<i64>, Option<String>) =
+                (row.get(9)?, row.get(10)?);
+
+            let changed = match (&previous_answer, &answer) {
+                (Some(previous), Some(answer)) => previous != answer,
+                _ => false,
+            };
+            if changed {
+                changes.push(format!(
+                    "Answer of part {part} ({variant}) changed from {} to {} on {time}{}",
+                    previous_answer.unwrap_or_default(),
+                    answer.as_deref().unwrap_or_default(),
+                    commit
+                        .as_ref()
+                        .map(|c| format!(" (commit {c})"))
+                        .unwrap_or_default()
+                ));
+            }
+            let change = match (previous_duration, duration) {
+                (Some(previous), Some(duration)) if previous > 0 => {
+                    Cell::Float((duration - previous) as f64 / previous as f64 * 100.)
+                }
+                _ => Cell::None,
+            };
+            table.push(vec![
+                time.into(),
+                commit.into(),
+                row.get::<_, String>(2)?.into(),
+                Cell::Int(part),
+                variant.into(),
+                row.get::<_, String>(5)?.into(),
+                answer.into(),
+                changed.into(),
+                duration.map(|d| Duration::from_nanos(d as u64)).into(),
+                change,
+                row.get::<_, Option<i64>>(8)?
+                    .map(|b| Cell::Bytes(b as usize))
+                    .into(),
+            ]);
+        }
+        Ok((table, changes))
+    }
+}
+
+/// The commit checked out in the working directory, suffixed with `-dirty` if it has changes.
+fn git_commit() -> Option<&'static str> {
+    static COMMIT: OnceLock<Option<String>> = OnceLock::new();
+    COMMIT
+        .get_or_init(|| {
+            let git = |args: &[&str]| {
+                Command::new("git")
+                    .args(args)
+                    .output()
+                    .ok()
+                    .filter(|o| o.status.success())
+                    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            };
+            let commit = git(&["rev-parse", "--short", "HEAD"])?;
+            match git(&["status", "--porcelain", "--untracked-files=no"]) {
+                Some(changes) if !changes.is_empty() => Some(format!("{commit}-dirty")),
+                _ => Some(commit),
+            }
+        })
+        .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(history: &History, command: &str, part: u8, answer: Option<&str>, nanos: u64) {
+        let record = Record {
+            variant: "default",
+            status: answer.map_or("panicked", |_| "ok"),
+            answer,
+            duration: answer.map(|_| Duration::from_nanos(nanos)),
+            ..Default::default()
+        };
+        let id = PuzzleId::new(2022, 1).unwrap().with_part(part).unwrap();
+        history.record(command, &id, &record).unwrap();
+    }
+
+    /// The part, answer, whether it changed and the change of time of each execution.
+    fn executions(history: &History, part: Option<u8>, limit: usize) -> (Vec<String>, usize) {
+        let mut id = PuzzleId::new(2022, 1).unwrap();
+        if let Some(part) = part {
+            id = id.with_part(part).unwrap();
+        }
+        let (table, changes) = history.executions(&id, limit).unwrap();
+        let csv = table.render(Format::Csv);
+        let rows = csv
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let cells: Vec<_> = line.split(',').collect();
+                [cells[2], cells[3], cells[6], cells[7], cells[9]].join(" ")
+            })
+            .collect();
+        (rows, changes.len())
+    }
+
+    #[test]
+    fn compares_executions_with_the_previous_ones() {
+        let history = History::open(Path::new(":memory:")).unwrap();
+        record(&history, "run", 1, Some("24000"), 100);
+        record(&history, "run", 2, Some("45000"), 300);
+        record(&history, "run", 1, Some("24000"), 150);
+        record(&history, "bench", 1, Some("24001"), 50);
+        record(&history, "run", 1, None, 0);
+        record(&history, "run", 1, Some("24000"), 120);
+        record(&history, "run", 2, Some("45000"), 150);
+
+        let (rows, changes) = executions(&history, None, 10);
+        assert_eq!(
+            rows,
+            [
+                "run 1 24000 false ",
+                "run 2 45000 false ",
+                // Times are compared with those of the same command, answers with any
+                "run 1 24000 false 50",
+                "bench 1 24001 true ",
+                "run 1  false ",
+                "run 1 24000 true ",
+                "run 2 45000 false -50",
+            ]
+        );
+        assert_eq!(changes, 2);
+
+        let (rows, _) = executions(&history, Some(2), 10);
+        assert_eq!(rows, ["run 2 45000 false ", "run 2 45000 false -50"]);
+        // The latest ones, still compared with the previous ones
+        let (rows, _) = executions(&history, None, 2);
+        assert_eq!(rows, ["run 1 24000 true ", "run 2 45000 false -50"]);
+    }
+}
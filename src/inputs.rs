@@ -0,0 +1,196 @@
+use crate::guard;
+use crate::output::{Cell, Column, Format, Table};
+use anyhow::{bail, Context, Result};
+use aoc_core::{AdventOfCodeRunnable, Input};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Extension of the files of known answers, next to the inputs they are for.
+pub static ANSWER_EXTENSION: &str = "answer";
+
+/// An input of a directory, e.g. one collected from a team member, with the known answers of its
+/// parts: the lines of its `.answer` file, like `alice.txt.answer` for `alice.txt`. Answers
+/// spanning several lines are written on one, with `\n` for the line breaks.
+pub struct DirInput {
+    pub name: String,
+    pub path: PathBuf,
+    pub answers: Vec<String>,
+}
+
+/// The inputs of the directory, sorted by name; hidden files and answer files are skipped.
+pub fn load_dir(dir: &Path) -> Result<Vec<DirInput>> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Could not read the directory {}", dir.display()))?;
+    let mut inputs = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) if !name.starts_with('.') => name.to_string(),
+            _ => continue,
+        };
+        if !path.is_file() || path.extension().is_some_and(|e| e == ANSWER_EXTENSION) {
+            continue;
+        }
+
+        // Named after the whole name of the input, not to be shared by alice.txt and alice.csv
+        let answer_path = dir.join(format!("{name}.{ANSWER_EXTENSION}"));
+        let answers = match answer_path.is_file() {
+            true => fs::read_to_string(&answer_path)
+                .with_context(|| format!("Could not read {}", answer_path.display()))?
+                .lines()
+                .map(|l| l.trim_end().replace("\\n", "\n"))
+                .collect(),
+            false => vec![],
+        };
+        inputs.push(DirInput {
+            name,
+            path,
+            answers,
+        });
+    }
+
+    if inputs.is_empty() {
+        bail!("No input in {}", dir.display());
+    }
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(inputs)
+}
+
+/// Runs the given parts of an exercise with the variant on every input of the directory, and
+/// prints their answers next to the known ones. Fails if any part failed or gave another answer.
+pub fn run(
+    ex: &'static dyn AdventOfCodeRunnable,
+    parts: &[u8],
+    variant: &str,
+    dir: &Path,
+    format: Format,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let mut solutions = vec![];
+    for part in parts.iter().copied() {
+        let variants = aoc_core::variants(&ex.id(), part);
+        let solve = crate::all_variants(ex, part, &variants)
+            .find_map(|(name, solve)| (name == variant).then_some(solve))
+            .with_context(|| format!("No variant {variant:?} for part {part} of {ex}"))?;
+        solutions.push((part, solve));
+    }
+
+    let mut table = Table::new(vec![
+        Column::new("input", "Input"),
+        Column::new("part", "Part"),
+        Column::new("answer", "Answer"),
+        Column::new("expected", "Expected"),
+        Column::new("duration_ns", "Time"),
+        Column::new("status", "Status"),
+        Column::new("error", "Error"),
+    ]);
+    let inputs = load_dir(dir)?;
+    let (mut n_mismatched, mut n_failed) = (0, 0);
+    for dir_input in &inputs {
+        let input = Input::open(&dir_input.path)
+            .with_context(|| format!("Could not read {}", dir_input.path.display()))
+            .map(Arc::new);
+        for (part, solve) in &solutions {
+            let expected = dir_input.answers.get(*part as usize - 1);
+            let run = match &input {
+                Ok(input) => guard::run(solve, input, timeout),
+                Err(e) => guard::Run::failed(guard::PartError::Failed(anyhow::anyhow!("{e}"))),
+            };
+
+            let (answer, elapsed, status, error) = match &run.answer {
+                Ok(answer) => {
+                    let status = match expected {
+                        Some(expected) if expected == answer => "pass",
+                        Some(_) => {
+                            n_mismatched += 1;
+                            "MISMATCH"
+                        }
+                        None => "unknown",
+                    };
+                    (Some(answer.as_str()), Some(run.elapsed), status, None)
+                }
+                Err(e) => {
+                    n_failed += 1;
+                    (None, None, e.status(), Some(e.to_string()))
+                }
+            };
+            table.push(vec![
+                dir_input.name.as_str().into(),
+                Cell::Int(*part as i64),
+                answer.into(),
+                expected.map(String::as_str).into(),
+                elapsed.into(),
+                match (status, format.is_human()) {
+                    ("MISMATCH", false) => "mismatch".into(),
+                    _ => status.into(),
+                },
+                error.into(),
+            ]);
+        }
+    }
+
+    table.print(format);
+    if format.is_human() {
+        println!(
+            "\n{} inputs of {ex}, {n_mismatched} mismatched answers, {n_failed} failed parts",
+            inputs.len()
+        );
+    }
+    if n_mismatched + n_failed > 0 {
+        bail!(
+            "{} of {} parts failed or don't give the known answer",
+            n_mismatched + n_failed,
+            inputs.len() * solutions.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_sorted_inputs_with_their_answers() {
+        let dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for (name, content) in [
+            ("carol.txt", "3"),
+            ("alice.txt", "1"),
+            ("alice.txt.answer", "24000\n#..#\\n#..#  \n"),
+            ("alice.csv", "1"),
+            ("alice.answer", "0\n"),
+            ("bob", "2"),
+            (".hidden.txt", "0"),
+        ] {
+            fs::write(dir.join(name), content).unwrap();
+        }
+
+        let inputs = load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        let inputs = inputs.unwrap();
+        let names: Vec<_> = inputs.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, ["alice.csv", "alice.txt", "bob", "carol.txt"]);
+        assert!(inputs[0].answers.is_empty());
+        assert_eq!(inputs[1].answers, ["24000", "#..#\n#..#"]);
+        assert!(inputs[2].answers.is_empty());
+        assert_eq!(inputs[3].path.file_name().unwrap(), "carol.txt");
+    }
+
+    #[test]
+    fn rejects_directories_without_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-no-inputs-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("alice.txt.answer"), "1\n").unwrap();
+        fs::write(dir.join(".gitkeep"), "").unwrap();
+
+        let e = load_dir(&dir).map(|_| ()).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(e.to_string().starts_with("No input in"), "{e}");
+        assert!(load_dir(&dir).is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::guard::{self, PartError};
+use crate::mem;
+use anyhow::{Context, Result};
+use aoc_core::{Input, PuzzleId};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// When to kill the process of a part.
+#[derive(Copy, Clone, Default)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// Resident memory, in bytes
+    pub memory: Option<usize>,
+}
+
+/// Resources used by the process of a part, from its start to its end.
+#[derive(Copy, Clone)]
+pub struct Resources {
+    pub wall: Duration,
+    /// User and system time
+    pub cpu: Duration,
+    /// Peak resident memory, in bytes
+    pub max_rss: usize,
+}
+
+/// Runs a part in a child process, i.e. the runner itself with the hidden `child` command, so that
+/// whatever it does (overflowing its stack, exhausting the memory...) can't affect the runner. The
+/// input of the part must be cached.
+#[cfg(unix)]
+pub fn run(id: PuzzleId, variant: &str, limits: Limits) -> Result<(guard::Run, Resources)> {
+    use std::process::{Command, Stdio};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Instant;
+
+    static N_CHILDREN: AtomicUsize = AtomicUsize::new(0);
+    let result_path = std::env::temp_dir().join(format!(
+        "aoc-{}-{}.result",
+        std::process::id(),
+        N_CHILDREN.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_file(&result_path);
+
+    let start = Instant::now();
+    let mut child = Command::new(std::env::current_exe()?)
+        .arg("child")
+        .arg(id.to_string())
+        .args(["--variant", variant])
+        .arg("--result")
+        .arg(&result_path)
+        .stdin(Stdio::null())
+        // Whatever the part prints mustn't end up in the table, e.g. in JSON
+        .stdout(std::io::stderr())
+        .spawn()
+        .context("Could not start the process of the part")?;
+    let pid = child.id() as libc::pid_t;
+
+    let mut killed = None;
+    let (status, usage) = loop {
+        let mut status = 0;
+        // SAFETY: rusage is plain data, filled by wait4
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        let flags = if killed.is_some() { 0 } else { libc::WNOHANG };
+        match unsafe { libc::wait4(pid, &mut status, flags, &mut usage) } {
+            r if r == pid => break (status, usage),
+            0 => {}
+            _ => {
+                let e = std::io::Error::last_os_error();
+                if e.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(e).context("Could not wait for the process of the part");
+                }
+                continue;
+            }
+        }
+
+        killed = match (limits.timeout, limits.memory) {
+            (Some(timeout), _) if start.elapsed() > timeout => Some(PartError::TimedOut(timeout)),
+            (_, Some(limit)) if resident_memory(pid).is_some_and(|rss| rss > limit) => {
+                Some(PartError::OutOfMemory(limit))
+            }
+            _ => None,
+        };
+        match killed {
+            Some(_) => child.kill()?,
+            None => std::thread::sleep(Duration::from_micros(500)),
+        }
+    };
+    let wall = start.elapsed();
+
+    let timeval = |t: libc::timeval| {
+        Duration::from_secs(t.tv_sec as u64) + Duration::from_micros(t.tv_usec as u64)
+    };
+    let resources = Resources {
+        wall,
+        cpu: timeval(usage.ru_utime) + timeval(usage.ru_stime),
+        // In kilobytes on Linux, but in bytes on macOS
+        max_rss: match cfg!(target_os = "macos") {
+            true => usage.ru_maxrss as usize,
+            false => usage.ru_maxrss as usize * 1024,
+        },
+    };
+
+    let result = fs::read_to_string(&result_path);
+    let _ = fs::remove_file(&result_path);
+    let run = match (killed, result) {
+        (Some(e), _) => guard::Run::failed(e),
+        (None, Ok(result)) if libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0 => {
+            parse_result(&result)
+        }
+        (None, _) if libc::WIFSIGNALED(status) => guard::Run::failed(PartError::Crashed(format!(
+            "killed by signal {}",
+            signal_name(libc::WTERMSIG(status))
+        ))),
+        (None, _) => guard::Run::failed(PartError::Crashed(format!(
+            "exited with code {}",
+            libc::WEXITSTATUS(status)
+        ))),
+    };
+    Ok((run, resources))
+}
+
+#[cfg(not(unix))]
+pub fn run(_id: PuzzleId, _variant: &str, _limits: Limits) -> Result<(guard::Run, Resources)> {
+    anyhow::bail!("Running parts in child processes is only supported on Unix")
+}
+
+/// Resident memory of the process, if it can be known.
+#[cfg(target_os = "linux")]
+fn resident_memory(pid: i32) -> Option<usize> {
+    // Second field of /proc/<pid>/statm, in pages
+    let statm = fs::read_to_string(format!("/proc/{pid}/statm")).ok()?;
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: sysconf has no precondition
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * page_size as usize)
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn resident_memory(_pid: i32) -> Option<usize> {
+    None
+}
+
+/// Whether the memory of the child processes can be limited on this platform.
+pub fn can_limit_memory() -> bool {
+    cfg!(target_os = "linux")
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> String {
+    let name = match signal {
+        libc::SIGABRT => "SIGABRT, e.g. on a stack overflow",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGBUS => "SIGBUS",
+        libc::SIGKILL => "SIGKILL",
+        _ => return signal.to_string(),
+    };
+    format!("{signal} ({name})")
+}
+
+/// Runs a part as the child process of [`run`], writing its outcome in the result file: its
+/// status, solve time (in ns) and heap usage on the first lines, then its answer or error.
+/// Failing to load the part is an error of the part too, not to be taken for a crash.
+pub fn child(id: PuzzleId, variant: &str, result_path: &Path) -> Result<()> {
+    let run = match load_part(id, variant) {
+        Ok((solve, input)) => guard::run(&solve, &input, None),
+        Err(e) => guard::Run::failed(PartError::Failed(anyhow::anyhow!("{e:#}"))),
+    };
+    let (status, text) = match &run.answer {
+        Ok(answer) => ("ok", answer.clone()),
+        Err(e) => (e.status(), e.to_string()),
+    };
+    let result = format!(
+        "{status}\n{}\n{} {} {}\n{text}",
+        run.elapsed.as_nanos(),
+        run.mem.peak,
+        run.mem.allocated,
+        run.mem.allocations
+    );
+    fs::write(result_path, result).context("Could not write the result of the part")
+}
+
+fn load_part(id: PuzzleId, variant: &str) -> Result<(guard::SolveFn, Arc<Input>)> {
+    let part = id.part().context("The part to run is missing")?;
+    let ex = aoc_core::registry()
+        .get(&id)
+        .with_context(|| format!("{id} is not implemented"))?;
+    let variants = aoc_core::variants(&id, part);
+    let solve = crate::all_variants(ex, part, &variants)
+        .find_map(|(name, solve)| (name == variant).then_some(solve))
+        .with_context(|| format!("No variant {variant:?} for {id}"))?;
+    let path = aoc_core::cached_input_path(&id);
+    let input = Input::open(&path)
+        .with_context(|| format!("Could not open the input {}", path.display()))?;
+    Ok((solve, Arc::new(input)))
+}
+
+/// The outcome of a part from the result file of its process, which crashed if the file is
+/// malformed, e.g. cut short.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_result(result: &str) -> guard::Run {
+    read_result(result).unwrap_or_else(|| {
+        guard::Run::failed(PartError::Crashed(format!(
+            "gave an invalid result {result:?}"
+        )))
+    })
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn read_result(result: &str) -> Option<guard::Run> {
+    let mut lines = result.splitn(4, '\n');
+    let status = lines.next().filter(|s| !s.is_empty())?;
+    let elapsed: u64 = lines.next()?.parse().ok()?;
+    let mem: Vec<usize> = lines
+        .next()?
+        .split(' ')
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    let text = lines.next().unwrap_or_default();
+    let (peak, allocated, allocations) = match mem[..] {
+        [peak, allocated, allocations] => (peak, allocated, allocations),
+        _ => return None,
+    };
+
+    Some(guard::Run {
+        answer: match status {
+            "ok" => Ok(text.to_string()),
+            // The error was already given its final form by the child
+            _ => Err(PartError::Reported {
+                status: status.to_string(),
+                message: text.to_string(),
+            }),
+        },
+        elapsed: Duration::from_nanos(elapsed),
+        mem: mem::Usage {
+            peak,
+            allocated,
+            allocations,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        let run = parse_result("ok\n1500\n1024 4096 12\n95437");
+        assert_eq!(run.answer.unwrap(), "95437");
+        assert_eq!(run.elapsed, Duration::from_nanos(1500));
+        assert_eq!(
+            (run.mem.peak, run.mem.allocated, run.mem.allocations),
+            (1024, 4096, 12)
+        );
+
+        // Answers can span several lines, or be empty
+        let run = parse_result("ok\n1\n0 0 0\n#..#\n#..#\n");
+        assert_eq!(run.answer.unwrap(), "#..#\n#..#\n");
+        assert_eq!(parse_result("ok\n1\n0 0 0").answer.unwrap(), "");
+    }
+
+    #[test]
+    fn reported_failures() {
+        let run = parse_result("panicked\n10\n0 0 0\npanicked at src/aoc_2022_2.rs:76:28: Q\nmore");
+        let e = run.answer.unwrap_err();
+        assert_eq!(e.status(), "panicked");
+        assert_eq!(
+            e.to_string(),
+            "panicked at src/aoc_2022_2.rs:76:28: Q\nmore"
+        );
+    }
+
+    #[test]
+    fn malformed_results_crashed() {
+        for result in [
+            "",
+            "ok",
+            "ok\n1500",
+            "\n1500\n0 0 0\n1",
+            "ok\n-1\n0 0 0\n1",
+            "ok\n1500\n0 0\n1",
+            "ok\n1500\n0 0 0 0\n1",
+            "ok\n1500\n0 x 0\n1",
+        ] {
+            let e = parse_result(result).answer.unwrap_err();
+            assert_eq!(e.status(), "crashed", "{result:?}");
+            assert!(e.to_string().contains("invalid result"), "{result:?}");
+        }
+    }
+
+    #[test]
+    fn children_report_their_errors() {
+        let path = std::env::temp_dir().join(format!("aoc-child-{}.result", std::process::id()));
+        let id = PuzzleId::new(2022, 25).unwrap().with_part(1).unwrap();
+        child(id, "", &path).unwrap();
+        let result = fs::read_to_string(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let e = parse_result(&result).answer.unwrap_err();
+        assert_eq!(e.status(), "error");
+        assert!(e.to_string().contains("is not implemented"), "{e}");
+    }
+}
@@ -1,119 +1,1318 @@
-#![feature(iter_array_chunks)]
-#![feature(slice_take)]
-#![feature(get_many_mut)]
-
-use anyhow::{Error, Result};
-use aoc_core::AdventOfCodeRunnable;
-use clap::{arg, value_parser, ArgAction, Command};
-use macro_support::{advent_of_code, declare_exercise_modules, get_available_exercises};
+use anyhow::{bail, Context, Error, Result};
+use aoc_core::{AdventOfCodeRunnable, Input, PuzzleId, Settings, Solver};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::parser::ValueSource;
+use clap::{arg, Arg, ArgAction, ArgMatches, Command};
+use config::Config;
+use guard::{PartError, SolveFn};
+use history::History;
+use macro_support::advent_of_code;
+use output::{Cell, Column, Format, Table};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread::sleep;
+use std::time::Duration;
 use std::{fs, time};
 
-#[advent_of_code(1, 2022)]
-struct CalorieCounting;
+mod bench;
+mod config;
+mod guard;
+mod history;
+mod inputs;
+mod isolate;
+mod mem;
+mod output;
+mod profile;
+mod readme;
+mod scaffold;
+mod tui;
+mod verify;
 
-#[advent_of_code(2, 2022)]
-struct RockPaperScissors;
-
-#[advent_of_code(3, 2022)]
-struct RucksackReorganization;
-
-#[advent_of_code(4, 2022)]
-struct CampCleanup;
+#[global_allocator]
+static ALLOCATOR: mem::CountingAllocator = mem::CountingAllocator;
 
+// Keeps the name it had before its title was fixed
 #[advent_of_code(5, 2022)]
 struct SupplyStack;
 
-#[advent_of_code(6, 2022)]
-struct TuningTrouble;
-
-#[advent_of_code(7, 2022)]
-struct NoSpaceLeftOnDevice;
-
-#[advent_of_code(8, 2022)]
-struct TreetopTreeHouse;
-
-declare_exercise_modules!();
+// One #[advent_of_code] struct per other src/aoc_<year>_<day>.rs module, see build.rs
+include!(concat!(env!("OUT_DIR"), "/exercises.rs"));
 
 fn main() -> Result<()> {
     let matches = Command::new("AdventOfCode Runner")
         .about("Utility to run advent of code implementations")
         .version("v0.1.0")
         .author("David Taralla (@dtaralla on GitHub)")
+        .arg(
+            arg!(--"config-profile" <NAME> "Profile of aoc.toml whose session to use")
+                .global(true),
+        )
+        .arg(
+            arg!(--offline "Only use cached inputs, never download anything")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
         .subcommand(Command::new("clearcache").about("Clear the cache of downloaded inputs"))
-        .subcommand(Command::new("ls").about("Lists all days that can be run"))
+        .subcommand(
+            Command::new("ls")
+                .about("Lists all days that can be run")
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("new")
+                .about("Creates the module of a new day and downloads its input")
+                .arg(arg!(--id <ID> "The OAUTH session ID (cookie) for adventofcode.com"))
+                .arg(arg!([YEAR]
+                    "Year of the exercise to create, or its day for the default year (today's \
+                    puzzle if not given)"
+                ))
+                .arg(arg!([DAY] "Day of the exercise to create").value_parser(parse_day))
+                .arg(arg!(--template <FILE>
+                    "Template of the module instead of src/template.rs, where {{year}}, {{day}}, \
+                    {{title}} and {{example}} are replaced"
+                )),
+        )
         .subcommand(
             Command::new("run")
                 .about("Runs the given exercise")
                 .arg(
                     arg!(--id <ID> 
                         "The OAUTH session ID (cookie) for adventofcode.com (if not given expects \
-                        to find it as the content (no BOM!) of a file session_id next to this executable)"),
+                        to find it in aoc.toml, or as the content (no BOM!) of a file session_id \
+                        next to this executable)"),
+                )
+                .arg(arg!([YEAR]
+                    "Year of the exercise to run, the whole puzzle like 2022/7 or 2022-07-2, or \
+                    its day for the default year (today's puzzle if not given)"
+                ))
+                .arg(
+                    arg!([DAY] "Day of the exercise to run, several like 1..=8 or 1,3,5, or all")
+                        .value_parser(parse_days_or_all),
+                )
+                .arg(
+                    arg!(--all "Run every exercise of the year")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["DAY", "second", "variant", "all-variants"]),
                 )
-                .arg(arg!(<YEAR> "Year of the exercise to run").value_parser(value_parser!(u16)))
-                .arg(arg!(<DAY> "Day of the exercise to run").value_parser(value_parser!(u8)))
                 .arg(
                     arg!(-s --second "Whether to execute the Second part of the exercise")
                         .action(ArgAction::SetTrue),
-                ),
+                )
+                .arg(
+                    arg!(--variant <NAME> "Variant of the solution to run (registered with #[aoc_solver])")
+                        .conflicts_with("all-variants"),
+                )
+                .arg(
+                    arg!(--"all-variants" "Run every variant of the solution and check they agree")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    arg!(--mem "Report the peak heap usage, bytes allocated and allocation count")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(timeout_arg())
+                .arg(
+                    arg!(--isolate
+                        "Run each part in a child process, reporting its wall time, CPU time and \
+                        max RSS, and surviving its crashes (e.g. stack overflows)"
+                    )
+                    .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    arg!(--"memory-limit" <SIZE>
+                        "Kill the child process of a part using more memory, like 512M or 2G"
+                    )
+                    .value_parser(parse_size)
+                    .requires("isolate"),
+                )
+                .arg(
+                    arg!(--"inputs-dir" <DIR>
+                        "Run the day on every input of the directory instead, checking the answers \
+                        of those with a .answer file (one line per part)"
+                    )
+                    .conflicts_with_all(["all-variants", "isolate"]),
+                )
+                .arg(
+                    arg!(--profile <SVG>
+                        "Profile the part by sampling it while it runs (again and again for a \
+                        second), and write a flamegraph to the file and the samples in the pprof \
+                        format next to it (.pb)"
+                    )
+                    .conflicts_with_all(["all-variants", "isolate", "inputs-dir"]),
+                )
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("child")
+                .about("Runs a part for `run --isolate`, whose input must be cached")
+                .hide(true)
+                .arg(arg!(<PUZZLE> "The part to run, like 2022/7/1"))
+                .arg(arg!(--variant <NAME> "Variant of the solution to run").default_value("default"))
+                .arg(arg!(--result <FILE> "File of the outcome of the part").required(true)),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Benchmarks the given exercises")
+                .arg(arg!(--id <ID> "The OAUTH session ID (cookie) for adventofcode.com"))
+                .arg(arg!(<YEAR>
+                    "Year of the exercises to benchmark, or their days for the default year"
+                ))
+                .arg(
+                    arg!([DAY] "Day of the exercise to benchmark, several like 1..=8 or 1,3,5, or all")
+                        .value_parser(parse_days_or_all),
+                )
+                .arg(
+                    arg!(--warmup <N> "Runs of each part before measuring")
+                        .value_parser(clap::value_parser!(u32))
+                        .default_value("10"),
+                )
+                .arg(
+                    arg!(--iterations <N> "Measured runs of each part")
+                        .value_parser(clap::value_parser!(u32).range(1..))
+                        .default_value("100"),
+                )
+                .arg(arg!(--save <FILE> "Save the median solve times in a baseline file"))
+                .arg(arg!(--baseline <FILE> "Compare the median solve times with a baseline file"))
+                .arg(
+                    arg!(--threshold <PERCENT> "Slowdown from the baseline reported as a regression")
+                        .value_parser(clap::value_parser!(f64))
+                        .default_value("10"),
+                )
+                .arg(timeout_arg())
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Checks the answers of every exercise with its cached input against known ones")
+                .arg(
+                    arg!(--manifest <FILE> "File of the known answers")
+                        .default_value(verify::DEFAULT_MANIFEST),
+                )
+                .arg(
+                    arg!(--record "Add the answers missing from the manifest to it")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(timeout_arg())
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("readme")
+                .about("Updates the progress section of the README")
+                .arg(arg!(--file <FILE> "README to update").default_value("README.md"))
+                .arg(
+                    arg!(--manifest <FILE> "File of the known answers")
+                        .default_value(verify::DEFAULT_MANIFEST),
+                )
+                .arg(arg!(--baseline <FILE> "Baseline file of the median solve times"))
+                .arg(timeout_arg()),
+        )
+        .subcommand(
+            Command::new("history")
+                .about("Shows the recorded executions of a day by run and bench")
+                .arg(arg!(<YEAR> "Year of the exercise").value_parser(parse_year))
+                .arg(arg!(<DAY> "Day of the exercise").value_parser(parse_day))
+                .arg(
+                    arg!(--part <PART> "Only show this part")
+                        .value_parser(clap::value_parser!(u8).range(1..=2)),
+                )
+                .arg(
+                    arg!(--limit <N> "Most recent executions shown")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("50"),
+                )
+                .arg(format_arg()),
+        )
+        .subcommand(
+            Command::new("tui")
+                .about("Shows the calendar of a year, to run its days and read their puzzles")
+                .arg(arg!(--id <ID> "The OAUTH session ID (cookie) for adventofcode.com"))
+                .arg(
+                    arg!([YEAR] "Year of the calendar (the one of the default puzzle if not given)")
+                        .value_parser(parse_year),
+                )
+                .arg(timeout_arg()),
         )
         .get_matches();
 
-    let es: Vec<Box<dyn AdventOfCodeRunnable>> = get_available_exercises!();
+    let config = Config::load()?;
+    aoc_core::configure(Settings {
+        cache_dir: config
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| aoc_core::CACHE_DIR.into()),
+        offline: matches.get_flag("offline") || config.offline.unwrap_or(false),
+        user_agent: config.user_agent.clone(),
+    })?;
 
     if matches.subcommand_matches("clearcache").is_some() {
-        fs::remove_dir_all(aoc_core::CACHE_DIR)?;
-        sleep(time::Duration::from_millis(100));
-        fs::create_dir(aoc_core::CACHE_DIR)?;
-        fs::write(format!("{}/.keep", aoc_core::CACHE_DIR), "")?;
+        let cache_dir = &aoc_core::settings().cache_dir;
+        if cache_dir.exists() {
+            fs::remove_dir_all(cache_dir)?;
+            sleep(time::Duration::from_millis(100));
+        }
+        fs::create_dir_all(cache_dir)?;
+        fs::write(cache_dir.join(".keep"), "")?;
         return Ok(());
     }
 
-    if matches.subcommand_matches("ls").is_some() {
-        for e in es.iter() {
-            println!("{e}");
+    if let Some(child_cmd) = matches.subcommand_matches("child") {
+        return isolate::child(
+            child_cmd.get_one::<String>("PUZZLE").unwrap().parse()?,
+            child_cmd.get_one::<String>("variant").unwrap(),
+            Path::new(child_cmd.get_one::<String>("result").unwrap()),
+        );
+    }
+
+    if let Some(ls_cmd) = matches.subcommand_matches("ls") {
+        print_exercises_table(
+            aoc_core::registry().as_slice(),
+            output_format(ls_cmd, &config),
+        );
+        return Ok(());
+    }
+
+    if let Some(new_cmd) = matches.subcommand_matches("new") {
+        let id = match (
+            new_cmd.get_one::<String>("YEAR"),
+            new_cmd.get_one::<u8>("DAY"),
+        ) {
+            (None, _) => default_puzzle(&config)?,
+            (Some(year), Some(day)) => PuzzleId::new(parse_year(year)?, *day)?,
+            (Some(day), None) if parse_year(day).is_err() => {
+                PuzzleId::new(default_year(&config)?, parse_day(day)?)?
+            }
+            (Some(year), None) => bail!("Expected the day after the year {year}"),
+        };
+        let template = match new_cmd.get_one::<String>("template") {
+            Some(file) => fs::read_to_string(file)
+                .with_context(|| format!("Could not read the template {file}"))?,
+            None => scaffold::DEFAULT_TEMPLATE.to_string(),
+        };
+
+        let puzzle = scaffold::Puzzle::fetch(id);
+        let path = scaffold::create_module(&puzzle, &template)?;
+        println!("Created {} for {:?}", path.display(), puzzle.title);
+
+        match session_id(new_cmd, &config) {
+            Ok(session_id) => {
+                aoc_core::get_input(session_id.trim(), &id, false)
+                    .context("Could not download the input")?;
+                println!(
+                    "Downloaded the input to {}",
+                    aoc_core::cached_input_path(&id).display()
+                );
+            }
+            Err(_) => println!("No session ID, the input will be downloaded on the first run"),
         }
         return Ok(());
     }
 
+    if let Some(bench_cmd) = matches.subcommand_matches("bench") {
+        let session_id = session_id(bench_cmd, &config)?;
+        let (year, days) = year_and_days(bench_cmd, &config)?;
+        let options = bench::Options {
+            warmup: *bench_cmd.get_one("warmup").unwrap(),
+            iterations: *bench_cmd.get_one("iterations").unwrap(),
+            timeout: timeout(bench_cmd),
+        };
+        let baseline = match bench_cmd.get_one::<String>("baseline") {
+            Some(file) => Some(bench::Baseline::load(Path::new(file))?),
+            None => None,
+        };
+
+        let history = open_history(&config);
+        let mut benches = vec![];
+        let mut n_failed = 0;
+        for ex in select_exercises(year, days.as_ref())? {
+            match bench::bench(ex, session_id.trim(), &options) {
+                Ok(b) => {
+                    for part in &b {
+                        record(
+                            history.as_ref(),
+                            "bench",
+                            &part.id,
+                            &history::Record::of_bench(part),
+                        );
+                    }
+                    benches.extend(b)
+                }
+                Err(e) => {
+                    eprintln!("Benchmark of {ex} failed: {e}");
+                    n_failed += 1;
+                }
+            }
+        }
+        let n_failed_parts = benches.iter().filter(|b| b.measures.is_err()).count();
+
+        let threshold = *bench_cmd.get_one::<f64>("threshold").unwrap();
+        print_bench_table(
+            &benches,
+            baseline.as_ref(),
+            threshold,
+            output_format(bench_cmd, &config),
+        );
+        if let Some(file) = bench_cmd.get_one::<String>("save") {
+            bench::Baseline::save(Path::new(file), &benches)?;
+        }
+
+        let n_regressed = baseline.map_or(0, |baseline| {
+            benches
+                .iter()
+                .filter(|b| baseline.regressed(b, threshold))
+                .count()
+        });
+        if n_failed > 0 {
+            bail!("{n_failed} exercises failed");
+        }
+        if n_failed_parts > 0 {
+            bail!("{n_failed_parts} of {} parts failed", benches.len());
+        }
+        if n_regressed > 0 {
+            bail!("{n_regressed} parts regressed by more than {threshold}%");
+        }
+        return Ok(());
+    }
+
+    if let Some(verify_cmd) = matches.subcommand_matches("verify") {
+        let path = Path::new(verify_cmd.get_one::<String>("manifest").unwrap());
+        let record = verify_cmd.get_flag("record");
+        let mut manifest = verify::Manifest::load(path)?;
+
+        let format = output_format(verify_cmd, &config);
+        let mut matrix = Table::new(vec![
+            Column::human("Exercise"),
+            Column::human("Part 1"),
+            Column::human("Part 2"),
+        ]);
+        let mut parts = Table::new(vec![
+            Column::data("year"),
+            Column::data("day"),
+            Column::data("part"),
+            Column::data("status"),
+            Column::data("expected"),
+            Column::data("answer"),
+            Column::data("error"),
+        ]);
+        let mut failures = vec![];
+        let mut n_missing = 0;
+        for ex in aoc_core::registry() {
+            let statuses = verify::verify(ex, &mut manifest, record, timeout(verify_cmd))?;
+            for (part, status) in (1..).zip(statuses.iter()) {
+                let mut error = None;
+                match status {
+                    verify::Status::Fail { expected, got } => {
+                        failures.push(format!("{ex}, part {part}: expected {expected}, got {got}"))
+                    }
+                    verify::Status::Error(e) => {
+                        failures.push(format!("{ex}, part {part}: {e}"));
+                        error = Some(e.as_str());
+                    }
+                    verify::Status::Missing(_) => n_missing += 1,
+                    _ => {}
+                }
+                parts.push(vec![
+                    ex.id().year().into(),
+                    ex.id().day().into(),
+                    Cell::Int(part),
+                    status.key().into(),
+                    status.expected().into(),
+                    status.answer().into(),
+                    error.into(),
+                ]);
+            }
+            matrix.push(vec![
+                ex.to_string().into(),
+                statuses[0].label().into(),
+                statuses[1].label().into(),
+            ]);
+        }
+
+        if format.is_human() {
+            matrix.print(format);
+            if !failures.is_empty() {
+                println!("\n{}", failures.join("\n"));
+            }
+        } else {
+            parts.print(format);
+        }
+        if record && n_missing > 0 {
+            manifest.save(path)?;
+            eprintln!("\nRecorded {n_missing} answers in {}", path.display());
+        }
+        if !failures.is_empty() {
+            bail!("{} parts don't give the known answer", failures.len());
+        }
+        return Ok(());
+    }
+
+    if let Some(readme_cmd) = matches.subcommand_matches("readme") {
+        let path = readme_cmd.get_one::<String>("file").unwrap();
+        let mut manifest =
+            verify::Manifest::load(Path::new(readme_cmd.get_one::<String>("manifest").unwrap()))?;
+        let baseline = match readme_cmd.get_one::<String>("baseline") {
+            Some(file) => Some(bench::Baseline::load(Path::new(file))?),
+            None => None,
+        };
+
+        let table = readme::progress_table(
+            aoc_core::registry().as_slice(),
+            &mut manifest,
+            baseline.as_ref(),
+            timeout(readme_cmd),
+        )?;
+        let content = fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?;
+        fs::write(path, readme::replace_section(&content, &table)?)?;
+        println!("Updated the progress section of {path}");
+        return Ok(());
+    }
+
+    if let Some(history_cmd) = matches.subcommand_matches("history") {
+        let mut id = PuzzleId::new(
+            *history_cmd.get_one::<u16>("YEAR").unwrap(),
+            *history_cmd.get_one::<u8>("DAY").unwrap(),
+        )?;
+        if let Some(part) = history_cmd.get_one::<u8>("part") {
+            id = id.with_part(*part)?;
+        }
+        let path = history_path(&config);
+        if !path.is_file() {
+            bail!("No history yet in {}", path.display());
+        }
+        return History::open(&path)?.print(
+            &id,
+            *history_cmd.get_one::<usize>("limit").unwrap(),
+            output_format(history_cmd, &config),
+        );
+    }
+
+    if let Some(tui_cmd) = matches.subcommand_matches("tui") {
+        let id = match tui_cmd.get_one::<u16>("YEAR") {
+            Some(year) => PuzzleId::new(*year, 1)?,
+            None => default_puzzle(&config)?,
+        };
+        let session_id = session_id(tui_cmd, &config).ok();
+        return tui::run(
+            id,
+            session_id.map(|s| s.trim().to_string()),
+            timeout(tui_cmd),
+        );
+    }
+
     let run_cmd = matches.subcommand_matches("run");
     if run_cmd.is_none() {
         return Err(Error::msg("Not a valid subcommand"));
     }
 
     let run_cmd = run_cmd.unwrap();
-    let session_id = if let Some(id) = run_cmd.get_one::<String>("id") {
-        id.clone()
-    } else {
-        fs::read_to_string("session_id")?
+    let session_id = session_id(run_cmd, &config)?;
+    let year_or_puzzle = run_cmd.get_one::<String>("YEAR");
+    let (year, days, part) = match year_or_puzzle.map(|s| s.parse::<PuzzleId>()) {
+        _ if run_cmd.get_flag("all") => match year_or_puzzle {
+            Some(year) => (parse_year(year)?, None, None),
+            None => (default_year(&config)?, None, None),
+        },
+        Some(Ok(id)) if !run_cmd.contains_id("DAY") => (id.year(), Some(vec![id.day()]), id.part()),
+        _ => {
+            let (year, days) = year_and_days(run_cmd, &config)?;
+            (year, days, None)
+        }
+    };
+    if days.as_ref().is_none_or(|days| days.len() > 1) {
+        if ["second", "variant", "all-variants", "inputs-dir", "profile"]
+            .iter()
+            .any(|arg| run_cmd.value_source(arg) == Some(ValueSource::CommandLine))
+        {
+            bail!(
+                "--second, --variant, --all-variants, --inputs-dir and --profile only apply to \
+                a single day"
+            );
+        }
+
+        let es = select_exercises(year, days.as_ref())?;
+        return run_batch(
+            &es,
+            session_id.trim(),
+            output_format(run_cmd, &config),
+            run_cmd.get_flag("mem"),
+            limits(run_cmd)?,
+            run_cmd.get_flag("isolate"),
+            open_history(&config).as_ref(),
+        );
+    }
+
+    let id = match part {
+        Some(part) => PuzzleId::new(year, days.unwrap()[0])?.with_part(part)?,
+        None => PuzzleId::new(year, days.unwrap()[0])?,
     };
-    let year: u16 = *run_cmd.get_one("YEAR").unwrap();
-    let day: u8 = *run_cmd.get_one("DAY").unwrap();
+    let day = id.day();
 
-    let mut selected_ex: Option<&Box<dyn AdventOfCodeRunnable>> = None;
-    for ex in es.iter() {
-        if ex.matches(day, year) {
-            selected_ex = Some(ex);
+    let selected_ex = match aoc_core::registry().get(&id) {
+        Some(ex) => ex,
+        None => bail!("Exercise of Dec {day}, {year} is not implemented"),
+    };
+    let part = match (id.part(), run_cmd.get_flag("second")) {
+        (Some(1), true) => bail!("{id} designates the first part, but --second was given"),
+        (Some(part), _) => part,
+        (None, second) => {
+            if second {
+                2
+            } else {
+                1
+            }
         }
+    };
+    let variant = run_cmd
+        .get_one::<String>("variant")
+        .map(String::as_str)
+        .unwrap_or("default");
+    if let Some(dir) = run_cmd.get_one::<String>("inputs-dir") {
+        let parts: Vec<u8> = match id.part().is_some() || run_cmd.get_flag("second") {
+            true => vec![part],
+            false => [1, 2]
+                .into_iter()
+                .filter(|p| selected_ex.is_implemented(*p))
+                .collect(),
+        };
+        return inputs::run(
+            selected_ex,
+            &parts,
+            variant,
+            Path::new(dir),
+            output_format(run_cmd, &config),
+            timeout(run_cmd),
+        );
+    }
+
+    if !selected_ex.is_implemented(part) {
+        bail!("Part {part} of Dec {day}, {year} is not implemented");
     }
+    let id = id.with_part(part)?;
+
+    let input = Arc::new(selected_ex.get_input(session_id.trim(), part == 2)?);
+    let history = open_history(&config);
+    let variants = aoc_core::variants(&id, part);
 
-    if selected_ex.is_none() {
-        println!("Exercise of Dec {day}, {year} is not implemented. Exiting...");
+    let format = output_format(run_cmd, &config);
+    let show_mem = run_cmd.get_flag("mem");
+    let (limits, isolated) = (limits(run_cmd)?, run_cmd.get_flag("isolate"));
+    let all_variants_flag = run_cmd.get_flag("all-variants");
+    let mut results = Table::new(run_columns(all_variants_flag, show_mem, isolated));
+    let mut push_result = |variant: &str, run: &guard::Run, process: Option<isolate::Resources>| {
+        results.push(run_row(
+            selected_ex,
+            part,
+            variant,
+            run,
+            process,
+            show_mem,
+            isolated,
+        ));
+        if format != Format::Text {
+            return;
+        }
+
+        // Failures of a single variant are reported as the error of the command
+        let (answer, elapsed, usage) = match (&run.answer, all_variants_flag) {
+            (Ok(answer), _) => (answer, run.elapsed, run.mem),
+            (Err(e), true) => return println!("Result ({variant}): {e}"),
+            (Err(_), false) => return,
+        };
+        match all_variants_flag {
+            true => println!("Result ({variant}): {answer} ({elapsed:?})"),
+            false => println!("Result: {answer}"),
+        }
+        if show_mem {
+            println!(
+                "Memory: {} peak, {} allocated in {} allocations",
+                output::human_bytes(usage.peak),
+                output::human_bytes(usage.allocated),
+                usage.allocations
+            );
+        }
+        if let Some(process) = process {
+            println!(
+                "Process: {:?} wall, {:?} CPU, {} max RSS",
+                process.wall,
+                process.cpu,
+                output::human_bytes(process.max_rss)
+            );
+        }
+    };
+    let mut run_and_record = |variant: &str, solve: &SolveFn| {
+        let (run, process) = run_part(&id, variant, solve, &input, limits, isolated)
+            .unwrap_or_else(|e| (guard::Run::failed(PartError::Failed(e)), None));
+        record(
+            history.as_ref(),
+            "run",
+            &id,
+            &history::Record::of_run(variant, &run, process),
+        );
+        push_result(variant, &run, process);
+        run
+    };
+
+    if all_variants_flag {
+        let (mut answers, mut failed) = (vec![], vec![]);
+        for (variant, solve) in all_variants(selected_ex, part, &variants) {
+            match run_and_record(variant, &solve).answer {
+                Ok(answer) => answers.push(answer),
+                Err(_) => failed.push(variant),
+            }
+        }
+
+        if format != Format::Text {
+            results.print(format);
+        }
+        if !failed.is_empty() {
+            bail!(
+                "Variants {} of part {part} of Dec {day}, {year} failed",
+                failed.join(", ")
+            );
+        }
+        if answers.iter().any(|r| *r != answers[0]) {
+            bail!("Variants of part {part} of Dec {day}, {year} disagree");
+        }
         return Ok(());
     }
 
-    let selected_ex = selected_ex.unwrap();
-    let result = match run_cmd.get_flag("second") {
-        true => {
-            let input = selected_ex.get_input(session_id.trim(), true)?;
-            selected_ex.run2(&input)?
+    let solve = all_variants(selected_ex, part, &variants)
+        .find_map(|(name, solve)| (name == variant).then_some(solve));
+    match solve {
+        Some(solve) => {
+            let run = run_and_record(variant, &solve);
+            if format != Format::Text {
+                results.print(format);
+            }
+            run.answer?;
+
+            if let Some(svg_path) = run_cmd.get_one::<String>("profile") {
+                let profile = profile::profile(&solve, &input, Path::new(svg_path))?;
+                eprintln!(
+                    "Profiled {} runs ({} samples) in {svg_path} and {}",
+                    profile.runs,
+                    profile.samples,
+                    profile.pprof_path.display()
+                );
+            }
         }
-        false => {
-            let input = selected_ex.get_input(session_id.trim(), false)?;
-            selected_ex.run(&input)?
+        None => {
+            let names: Vec<&str> = all_variants(selected_ex, part, &variants)
+                .map(|(name, _)| name)
+                .collect();
+            bail!(
+                "No variant {variant:?} for part {part} of Dec {day}, {year}; available: {}",
+                names.join(", ")
+            );
         }
     };
+    Ok(())
+}
+
+/// The registered exercises of the given days, or of the whole year.
+fn select_exercises(
+    year: u16,
+    days: Option<&Vec<u8>>,
+) -> Result<Vec<&'static dyn AdventOfCodeRunnable>> {
+    let registry = aoc_core::registry();
+    let days = match days {
+        Some(days) => days,
+        None => return Ok(registry.year(year).to_vec()),
+    };
 
-    println!("Result: {result}");
+    let mut es = vec![];
+    for day in days {
+        match registry.get(&PuzzleId::new(year, *day)?) {
+            Some(ex) => es.push(ex),
+            None => eprintln!("Exercise of Dec {day}, {year} is not implemented, skipped"),
+        }
+    }
+    Ok(es)
+}
+
+/// Runs both parts of every given exercise, going on past errors and panics, and prints a summary
+/// of their answers. Fails if any part did.
+fn run_batch(
+    es: &[&'static dyn AdventOfCodeRunnable],
+    session_id: &str,
+    format: Format,
+    show_mem: bool,
+    limits: isolate::Limits,
+    isolated: bool,
+    history: Option<&History>,
+) -> Result<()> {
+    let mut table = Table::new(run_columns(false, show_mem, isolated));
+    let mut n_parts = 0;
+    let mut n_failed = 0;
+    let mut total = Duration::ZERO;
+
+    for ex in es.iter().copied() {
+        let input = ex.get_input(session_id, false).map(Arc::new);
+        for part in [1, 2].into_iter().filter(|p| ex.is_implemented(*p)) {
+            let id = ex.id().with_part(part)?;
+            let (run, process) = match &input {
+                Ok(input) => {
+                    let solve = guard::solution(ex, part);
+                    match run_part(&id, "default", &solve, input, limits, isolated) {
+                        Ok((run, process)) => {
+                            let execution = history::Record::of_run("default", &run, process);
+                            record(history, "run", &id, &execution);
+                            (run, process)
+                        }
+                        Err(e) => (guard::Run::failed(PartError::Failed(e)), None),
+                    }
+                }
+                Err(e) => (
+                    guard::Run::failed(PartError::Failed(anyhow::anyhow!(
+                        "Could not get the input: {e}"
+                    ))),
+                    None,
+                ),
+            };
+
+            match &run.answer {
+                Ok(_) => total += run.elapsed,
+                Err(_) => n_failed += 1,
+            }
+            table.push(run_row(
+                ex, part, "default", &run, process, show_mem, isolated,
+            ));
+            n_parts += 1;
+        }
+    }
+
+    table.print(format);
+    if format.is_human() {
+        println!("\n{n_parts} parts run in {total:?}, {n_failed} failed");
+    }
+    if n_failed > 0 {
+        bail!("{n_failed} of {n_parts} parts failed");
+    }
     Ok(())
 }
+
+/// Columns of the results of `run`, the same for a part and for several days. The variant is only
+/// shown to be read if several variants are run.
+fn run_columns(show_variant: bool, show_mem: bool, isolated: bool) -> Vec<Column> {
+    let mut columns = vec![
+        Column::human("Exercise"),
+        Column::data("year"),
+        Column::data("day"),
+        Column::data("title"),
+        Column::new("part", "Part"),
+        match show_variant {
+            true => Column::new("variant", "Variant"),
+            false => Column::data("variant"),
+        },
+        Column::new("answer", "Answer"),
+        Column::new("duration_ns", "Time"),
+    ];
+    if show_mem {
+        columns.extend(mem_columns());
+    }
+    if isolated {
+        columns.extend(process_columns());
+    }
+    columns.extend([
+        Column::new("status", "Status"),
+        Column::new("error", "Error"),
+    ]);
+    columns
+}
+
+/// Row of [`run_columns`] for a run of a part, successful or not.
+fn run_row(
+    ex: &dyn AdventOfCodeRunnable,
+    part: u8,
+    variant: &str,
+    run: &guard::Run,
+    process: Option<isolate::Resources>,
+    show_mem: bool,
+    isolated: bool,
+) -> Vec<Cell> {
+    let (answer, elapsed, usage, status, error) = match &run.answer {
+        Ok(answer) => (
+            Some(answer.as_str()),
+            Some(run.elapsed),
+            Some(run.mem),
+            "ok",
+            None,
+        ),
+        Err(e) => (None, None, None, e.status(), Some(e.to_string())),
+    };
+    let mut row: Vec<Cell> = vec![
+        ex.to_string().into(),
+        ex.id().year().into(),
+        ex.id().day().into(),
+        ex.title().into(),
+        part.into(),
+        variant.into(),
+        answer.into(),
+        elapsed.into(),
+    ];
+    if show_mem {
+        row.extend(mem_cells(usage));
+    }
+    if isolated {
+        row.extend(process_cells(process));
+    }
+    row.extend([status.into(), error.into()]);
+    row
+}
+
+/// The history of the config, or else the default one; the executions aren't recorded if it
+/// can't be opened.
+fn open_history(config: &Config) -> Option<History> {
+    History::open(&history_path(config))
+        .inspect_err(|e| eprintln!("Executions won't be recorded: {e:#}"))
+        .ok()
+}
+
+/// The history of the config, or else the one next to the cache of the inputs.
+fn history_path(config: &Config) -> PathBuf {
+    config.history.clone().unwrap_or_else(|| {
+        let cache_dir = &aoc_core::settings().cache_dir;
+        cache_dir
+            .parent()
+            .unwrap_or(cache_dir)
+            .join(history::FILE_NAME)
+    })
+}
+
+fn record(history: Option<&History>, command: &str, id: &PuzzleId, record: &history::Record) {
+    if let Some(Err(e)) = history.map(|h| h.record(command, id, record)) {
+        eprintln!("Could not record the execution of {id}: {e:#}");
+    }
+}
+
+/// Runs a part in the runner, or else in a child process, with the resources it used.
+fn run_part(
+    id: &PuzzleId,
+    variant: &str,
+    solve: &SolveFn,
+    input: &Arc<Input>,
+    limits: isolate::Limits,
+    isolated: bool,
+) -> Result<(guard::Run, Option<isolate::Resources>)> {
+    match isolated {
+        true => {
+            let (run, process) = isolate::run(*id, variant, limits)?;
+            Ok((run, Some(process)))
+        }
+        false => Ok((guard::run(solve, input, limits.timeout), None)),
+    }
+}
+
+/// The exercise's own solution of the given part, named "default", followed by its variants.
+fn all_variants<'a>(
+    ex: &'static dyn AdventOfCodeRunnable,
+    part: u8,
+    variants: &'a [&'static Solver],
+) -> impl Iterator<Item = (&'static str, SolveFn)> + 'a {
+    std::iter::once(("default", guard::solution(ex, part))).chain(variants.iter().map(|s| {
+        let solve: SolveFn = Arc::new(s.solve);
+        (s.variant, solve)
+    }))
+}
+
+/// The session ID given to the command, or else the one of the config, or else the content of
+/// the session_id file. No session is needed offline.
+fn session_id(cmd: &ArgMatches, config: &Config) -> Result<String> {
+    if let Some(id) = cmd.get_one::<String>("id") {
+        return Ok(id.clone());
+    }
+    if let Some(session) =
+        config.session(cmd.get_one::<String>("config-profile").map(String::as_str))?
+    {
+        return Ok(session.to_string());
+    }
+    match fs::read_to_string("session_id") {
+        Ok(id) => Ok(id),
+        Err(_) if aoc_core::settings().offline => Ok(String::new()),
+        Err(e) => Err(e).context("No session ID given, nor in aoc.toml or a session_id file"),
+    }
+}
+
+/// The year and the days (`None` for all of them) given as `[YEAR] [DAY]`, where the year can be
+/// left out for the default one, e.g. `run 7`, and both for the default puzzle.
+fn year_and_days(cmd: &ArgMatches, config: &Config) -> Result<(u16, Option<Vec<u8>>)> {
+    let year = match cmd.get_one::<String>("YEAR") {
+        Some(year) => year,
+        None => {
+            let id = default_puzzle(config)?;
+            return Ok((id.year(), Some(vec![id.day()])));
+        }
+    };
+    match cmd.get_one::<Option<Vec<u8>>>("DAY") {
+        Some(days) => Ok((parse_year(year)?, days.clone())),
+        None if parse_year(year).is_err() => Ok((default_year(config)?, parse_days_or_all(year)?)),
+        None => bail!("Expected the day after the year {year}"),
+    }
+}
+
+/// The puzzle of today during an event (of the year of the config, if any), or else the latest
+/// implemented one.
+fn default_puzzle(config: &Config) -> Result<PuzzleId> {
+    if let Some(id) = PuzzleId::today().filter(|id| config.year.is_none_or(|y| y == id.year())) {
+        return Ok(id);
+    }
+
+    let registry = aoc_core::registry();
+    let es = match config.year {
+        Some(year) => registry.year(year),
+        None => registry.as_slice(),
+    };
+    es.last()
+        .map(|ex| ex.id())
+        .context("No puzzle given, and no exercise implemented to default to")
+}
+
+/// The year of the config, or else the one of the default puzzle.
+fn default_year(config: &Config) -> Result<u16> {
+    match config.year {
+        Some(year) => Ok(year),
+        None => default_puzzle(config).map(|id| id.year()),
+    }
+}
+
+fn parse_year(s: &str) -> Result<u16> {
+    PuzzleId::check_year(s.parse().with_context(|| format!("Invalid year {s:?}"))?)
+}
+
+fn parse_day(s: &str) -> Result<u8> {
+    PuzzleId::check_day(s.parse().with_context(|| format!("Invalid day {s:?}"))?)
+}
+
+/// Parses `all`, or the days like [`parse_days`].
+fn parse_days_or_all(s: &str) -> Result<Option<Vec<u8>>> {
+    match s {
+        "all" => Ok(None),
+        _ => parse_days(s).map(Some),
+    }
+}
+
+/// Parses a day, or a comma-separated list of days and ranges like `1..=3,5,7..9`, into sorted
+/// days without duplicates.
+fn parse_days(s: &str) -> Result<Vec<u8>> {
+    let mut days = vec![];
+    for item in s.split(',') {
+        match item.split_once("..") {
+            Some((first, last)) => {
+                let first = parse_day(first)?;
+                let last = match last.strip_prefix('=') {
+                    Some(last) => Some(parse_day(last)?),
+                    // The end may be the day after the last one, as in 1..26
+                    None => last
+                        .parse::<u8>()
+                        .with_context(|| format!("Invalid day {last:?}"))?
+                        .checked_sub(1),
+                };
+                match last {
+                    Some(last) if last >= first => days.extend(first..=PuzzleId::check_day(last)?),
+                    _ => bail!("Empty range of days {item:?}"),
+                }
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
+fn print_exercises_table(es: &[&dyn AdventOfCodeRunnable], format: Format) {
+    // Confirmed answer, implemented but unconfirmed, or not implemented at all
+    let part_status = |e: &dyn AdventOfCodeRunnable, part: u8| match part {
+        p if e.is_confirmed(p) => ("*", "confirmed"),
+        p if e.is_implemented(p) => ("o", "implemented"),
+        _ => ("-", "not_implemented"),
+    };
+
+    let mut table = Table::new(vec![
+        Column::human("Exercise"),
+        Column::data("year"),
+        Column::data("day"),
+        Column::new("title", "Title"),
+        Column::human("Part 1"),
+        Column::data("part1"),
+        Column::human("Part 2"),
+        Column::data("part2"),
+        Column::new("cached", "Cached"),
+        Column::new("tags", "Tags"),
+    ]);
+    for e in es.iter().copied() {
+        let (part1, part2) = (part_status(e, 1), part_status(e, 2));
+        table.push(vec![
+            e.to_string().into(),
+            e.id().year().into(),
+            e.id().day().into(),
+            e.title().into(),
+            part1.0.into(),
+            part1.1.into(),
+            part2.0.into(),
+            part2.1.into(),
+            e.is_input_cached().into(),
+            e.tags()
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .into(),
+        ]);
+    }
+
+    table.print(format);
+    if format.is_human() {
+        println!("\n*: answer confirmed, o: implemented, -: not implemented");
+    }
+}
+
+fn print_bench_table(
+    benches: &[bench::PartBench],
+    baseline: Option<&bench::Baseline>,
+    threshold: f64,
+    format: Format,
+) {
+    let mut columns = vec![
+        Column::human("Exercise"),
+        Column::data("year"),
+        Column::data("day"),
+        Column::new("part", "Part"),
+        Column::new("load_ns", "Load"),
+        Column::new("min_ns", "Min"),
+        Column::new("mean_ns", "Mean"),
+        Column::new("median_ns", "Median"),
+        Column::new("stddev_ns", "Std dev"),
+    ];
+    columns.extend(mem_columns());
+    if baseline.is_some() {
+        columns.extend([
+            Column::human("Baseline"),
+            Column::data("baseline_change_pct"),
+            Column::data("regressed"),
+        ]);
+    }
+    columns.extend([
+        Column::new("status", "Status"),
+        Column::new("error", "Error"),
+    ]);
+
+    let mut table = Table::new(columns);
+    for b in benches.iter() {
+        let solve = b.measures.as_ref().ok().map(|m| m.solve);
+        let mut row: Vec<Cell> = vec![
+            b.exercise.clone().into(),
+            b.id.year().into(),
+            b.id.day().into(),
+            b.id.part().into(),
+            b.load.median.into(),
+            solve.map(|s| s.min).into(),
+            solve.map(|s| s.mean).into(),
+            solve.map(|s| s.median).into(),
+            solve.map(|s| s.stddev).into(),
+        ];
+        row.extend(mem_cells(b.measures.as_ref().ok().map(|m| m.mem)));
+        if let Some(baseline) = baseline {
+            let change = baseline.change(b);
+            let regressed = baseline.regressed(b, threshold);
+            row.extend([
+                match change {
+                    Some(c) if regressed => format!("{c:+.1}% REGRESSED").into(),
+                    Some(c) => format!("{c:+.1}%").into(),
+                    None => Cell::None,
+                },
+                change.into(),
+                regressed.into(),
+            ]);
+        }
+        let (status, error) = match &b.measures {
+            Ok(_) => ("ok", None),
+            Err(e) => (e.status(), Some(e.to_string())),
+        };
+        row.extend([status.into(), error.into()]);
+        table.push(row);
+    }
+
+    table.print(format);
+}
+
+/// Columns of the heap usage of a part, see [`mem_cells`].
+fn mem_columns() -> [Column; 3] {
+    [
+        Column::new("peak_heap_bytes", "Peak heap"),
+        Column::new("allocated_bytes", "Allocated"),
+        Column::new("allocations", "Allocs"),
+    ]
+}
+
+fn mem_cells(usage: Option<mem::Usage>) -> [Cell; 3] {
+    match usage {
+        Some(usage) => [
+            Cell::Bytes(usage.peak),
+            Cell::Bytes(usage.allocated),
+            Cell::Int(usage.allocations as i64),
+        ],
+        None => [Cell::None, Cell::None, Cell::None],
+    }
+}
+
+/// Columns of the resources used by the child process of a part, see [`process_cells`].
+fn process_columns() -> [Column; 3] {
+    [
+        Column::new("wall_ns", "Wall"),
+        Column::new("cpu_ns", "CPU"),
+        Column::new("max_rss_bytes", "Max RSS"),
+    ]
+}
+
+fn process_cells(process: Option<isolate::Resources>) -> [Cell; 3] {
+    match process {
+        Some(process) => [
+            process.wall.into(),
+            process.cpu.into(),
+            Cell::Bytes(process.max_rss),
+        ],
+        None => [Cell::None, Cell::None, Cell::None],
+    }
+}
+
+fn timeout_arg() -> Arg {
+    arg!(--timeout <DURATION> "Give up on a part after this time, like 10s, 500ms or 2m")
+        .value_parser(parse_duration)
+}
+
+fn timeout(cmd: &ArgMatches) -> Option<Duration> {
+    cmd.get_one::<Duration>("timeout").copied()
+}
+
+/// The limits given to the command for each part.
+fn limits(cmd: &ArgMatches) -> Result<isolate::Limits> {
+    let memory = cmd.get_one::<usize>("memory-limit").copied();
+    if memory.is_some() && !isolate::can_limit_memory() {
+        bail!("--memory-limit is only supported on Linux");
+    }
+    Ok(isolate::Limits {
+        timeout: timeout(cmd),
+        memory,
+    })
+}
+
+/// Parses a size like `512M` or `2G`, in bytes if it has no unit.
+fn parse_size(s: &str) -> Result<usize> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid size {s:?}"))?;
+    let factor = match unit.trim_end_matches("iB").trim_end_matches('B') {
+        "" => 1,
+        "K" | "k" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        _ => bail!("Unknown unit {unit:?} in {s:?}, expected K, M or G"),
+    };
+    if value < 0. {
+        bail!("Invalid size {s:?}");
+    }
+    Ok((value * factor as f64) as usize)
+}
+
+/// Parses a duration like `500ms`, `10s` or `2m`, in seconds if it has no unit.
+fn parse_duration(s: &str) -> Result<Duration> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: f64 = value
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid duration {s:?}"))?;
+    let secs = match unit {
+        "ms" => value / 1000.,
+        "" | "s" => value,
+        "m" => value * 60.,
+        _ => bail!("Unknown unit {unit:?} in {s:?}, expected ms, s or m"),
+    };
+    Duration::try_from_secs_f64(secs).with_context(|| format!("Invalid duration {s:?}"))
+}
+
+fn format_arg() -> Arg {
+    arg!(--format <FORMAT> "Output format")
+        .value_parser(
+            PossibleValuesParser::new(Format::NAMES).map(|s| s.parse::<Format>().unwrap()),
+        )
+        .default_value("text")
+}
+
+/// The format given to the command, or else the one of the config.
+fn output_format(cmd: &ArgMatches, config: &Config) -> Format {
+    match (cmd.value_source("format"), config.format) {
+        (Some(ValueSource::DefaultValue), Some(format)) => format,
+        _ => *cmd.get_one::<Format>("format").unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_the_generated_table() {
+        let registry = aoc_core::registry();
+        assert!(std::ptr::eq(registry, &EXERCISE_TABLE));
+        assert_eq!(registry.len(), aoc_core::EXERCISES.len());
+        let ids: Vec<PuzzleId> = registry.iter().map(|e| e.id()).collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        for ex in registry {
+            assert!(std::ptr::eq(registry.get(&ex.id()).unwrap(), ex));
+        }
+    }
+
+    #[test]
+    fn parse_days_lists_and_ranges() {
+        assert_eq!(parse_days("7").unwrap(), [7]);
+        assert_eq!(parse_days("1..=3,5,7..9").unwrap(), [1, 2, 3, 5, 7, 8]);
+        assert_eq!(parse_days("1..26").unwrap(), (1..=25).collect::<Vec<_>>());
+        assert_eq!(parse_days("1..=25").unwrap().len(), 25);
+        assert_eq!(parse_days("3..4").unwrap(), [3]);
+    }
+
+    #[test]
+    fn parse_days_sorted_without_duplicates() {
+        assert_eq!(parse_days("9,1..=3,2,3..5").unwrap(), [1, 2, 3, 4, 9]);
+        assert_eq!(parse_days("5,5").unwrap(), [5]);
+    }
+
+    #[test]
+    fn parse_days_rejects_invalid_days() {
+        for s in [
+            "", "0", "26", "1..27", "0..3", "1..=26", "3..3", "3..1", "3..=2", "1,,2", "x", "1..x",
+            "..3",
+        ] {
+            assert!(parse_days(s).is_err(), "{s:?} was parsed");
+        }
+        assert_eq!(parse_days_or_all("all").unwrap(), None);
+        assert_eq!(parse_days_or_all("1,2").unwrap(), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn parse_size_units() {
+        assert_eq!(parse_size("4096").unwrap(), 4096);
+        assert_eq!(parse_size("1k").unwrap(), 1024);
+        assert_eq!(parse_size("512M").unwrap(), 512 << 20);
+        assert_eq!(parse_size("512MiB").unwrap(), 512 << 20);
+        assert_eq!(parse_size("1.5 GB").unwrap(), 3 << 29);
+        for s in ["", "M", "12T", "-1M", "1 0M", "NaN"] {
+            assert!(parse_size(s).is_err(), "{s:?} was parsed");
+        }
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("2").unwrap(), Duration::from_secs(2));
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+        for s in ["", "s", "10h", "-1s", "1e400"] {
+            assert!(parse_duration(s).is_err(), "{s:?} was parsed");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, keeping count of the heap used by the process.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record(grown: usize, shrunk: usize) {
+        ALLOCATIONS.fetch_add(1, Relaxed);
+        ALLOCATED.fetch_add(grown, Relaxed);
+        let in_use = IN_USE.fetch_add(grown, Relaxed) + grown;
+        PEAK.fetch_max(in_use, Relaxed);
+        IN_USE.fetch_sub(shrunk, Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        IN_USE.fetch_sub(layout.size(), Relaxed);
+    }
+
+    /// Counted as a new allocation, whose old block is only freed once the new one is in use.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Heap used while running some code.
+#[derive(Copy, Clone, Default)]
+pub struct Usage {
+    /// Most bytes in use at once, beyond those already in use before
+    pub peak: usize,
+    /// Bytes allocated in total, including the ones freed since
+    pub allocated: usize,
+    pub allocations: usize,
+}
+
+/// Runs the function, and measures its heap usage. Allocations of other threads are counted too,
+/// so it's only accurate while the runner does nothing else.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Usage) {
+    let in_use = IN_USE.load(Relaxed);
+    PEAK.store(in_use, Relaxed);
+    let allocated = ALLOCATED.load(Relaxed);
+    let allocations = ALLOCATIONS.load(Relaxed);
+
+    let result = f();
+    let usage = Usage {
+        peak: PEAK.load(Relaxed) - in_use,
+        allocated: ALLOCATED.load(Relaxed) - allocated,
+        allocations: ALLOCATIONS.load(Relaxed) - allocations,
+    };
+    (result, usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_allocations_and_reallocations() {
+        // Other tests allocate at the same time, hence the lower bounds
+        let (len, usage) = measure(|| {
+            let mut bytes: Vec<u8> = Vec::with_capacity(1 << 20);
+            bytes.resize(1 << 20, 1);
+            bytes.reserve_exact(3 << 20);
+            std::hint::black_box(&mut bytes).len()
+        });
+        assert_eq!(len, 1 << 20);
+        assert!(usage.allocations >= 2, "{} allocations", usage.allocations);
+        // The reallocation counts as an allocation of the whole new block
+        assert!(usage.allocated >= 5 << 20, "{} bytes allocated", usage.allocated);
+        assert!(usage.peak >= 4 << 20, "{} bytes at most", usage.peak);
+    }
+}
@@ -0,0 +1,393 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Deserializer};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Output format of the commands. Text and Markdown are meant to be read, JSON and CSV to be
+/// consumed by scripts: they only have one table, with stable keys and raw values.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 4] = ["text", "json", "csv", "markdown"];
+
+    pub fn is_human(&self) -> bool {
+        matches!(self, Format::Text | Format::Markdown)
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            "markdown" => Format::Markdown,
+            _ => bail!(
+                "Unknown format {s:?}, expected one of {}",
+                Self::NAMES.join(", ")
+            ),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Format {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+pub enum Cell {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// Shown like `24.837µs`, or as a number of nanoseconds in data formats
+    Duration(Duration),
+    /// Shown like `1.5 KiB`, or as a number of bytes in data formats
+    Bytes(usize),
+    List(Vec<String>),
+    None,
+}
+
+impl Cell {
+    fn human(&self) -> String {
+        match self {
+            Cell::Str(s) => s.clone(),
+            Cell::Int(i) => i.to_string(),
+            Cell::Float(f) => format!("{f:.1}"),
+            Cell::Bool(b) => if *b { "yes" } else { "no" }.to_string(),
+            Cell::Duration(d) => format!("{d:?}"),
+            Cell::Bytes(b) => human_bytes(*b),
+            Cell::List(l) => l.join(", "),
+            Cell::None => "-".to_string(),
+        }
+    }
+
+    fn json(&self) -> String {
+        match self {
+            Cell::Str(s) => json_string(s),
+            Cell::Int(i) => i.to_string(),
+            Cell::Float(f) if f.is_finite() => f.to_string(),
+            Cell::Float(_) | Cell::None => "null".to_string(),
+            Cell::Bool(b) => b.to_string(),
+            Cell::Duration(d) => d.as_nanos().to_string(),
+            Cell::Bytes(b) => b.to_string(),
+            Cell::List(l) => {
+                let items: Vec<String> = l.iter().map(|s| json_string(s)).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+
+    fn csv(&self) -> String {
+        let value = match self {
+            Cell::Str(s) => s.clone(),
+            Cell::Float(f) => f.to_string(),
+            Cell::Bool(b) => b.to_string(),
+            Cell::List(l) => l.join(";"),
+            Cell::None => String::new(),
+            Cell::Int(_) | Cell::Duration(_) | Cell::Bytes(_) => self.json(),
+        };
+
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(s: String) -> Self {
+        Cell::Str(s)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(s: &str) -> Self {
+        Cell::Str(s.to_string())
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::None, Into::into)
+    }
+}
+
+macro_rules! impl_cell_from {
+    ($variant:ident: $($t:ty),*) => {
+        $(
+            impl From<$t> for Cell {
+                fn from(value: $t) -> Self {
+                    Cell::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_cell_from!(Int: u8, u16, u32, i64);
+impl_cell_from!(Float: f64);
+impl_cell_from!(Bool: bool);
+impl_cell_from!(Duration: Duration);
+impl_cell_from!(List: Vec<String>);
+
+/// A column, shown in the human formats under its header and/or in the data formats under its
+/// key.
+pub struct Column {
+    key: Option<&'static str>,
+    header: Option<&'static str>,
+}
+
+impl Column {
+    pub fn new(key: &'static str, header: &'static str) -> Self {
+        Self {
+            key: Some(key),
+            header: Some(header),
+        }
+    }
+
+    /// A column only shown in the human formats, e.g. the name of the exercise.
+    pub fn human(header: &'static str) -> Self {
+        Self {
+            key: None,
+            header: Some(header),
+        }
+    }
+
+    /// A column only shown in the data formats, e.g. the year of the exercise.
+    pub fn data(key: &'static str) -> Self {
+        Self {
+            key: Some(key),
+            header: None,
+        }
+    }
+}
+
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    /// Adds a row, with a cell for each column.
+    pub fn push(&mut self, row: Vec<Cell>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn print(&self, format: Format) {
+        print!("{}", self.render(format));
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        // Names of the shown columns, and their positions in the rows
+        let columns: Vec<(usize, &str)> = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match format.is_human() {
+                true => c.header.map(|h| (i, h)),
+                false => c.key.map(|k| (i, k)),
+            })
+            .collect();
+        let cells = |row: &Vec<Cell>, f: fn(&Cell) -> String| -> Vec<String> {
+            columns.iter().map(|(i, _)| f(&row[*i])).collect()
+        };
+
+        let mut out = String::new();
+        match format {
+            Format::Text => {
+                let rows: Vec<Vec<String>> =
+                    self.rows.iter().map(|r| cells(r, Cell::human)).collect();
+                let mut widths: Vec<usize> =
+                    columns.iter().map(|(_, h)| h.chars().count()).collect();
+                for row in rows.iter() {
+                    for (w, cell) in widths.iter_mut().zip(row.iter()) {
+                        *w = (*w).max(cell.chars().count());
+                    }
+                }
+
+                let mut write_row = |cells: Vec<&str>| {
+                    let line: Vec<String> = cells
+                        .iter()
+                        .zip(widths.iter())
+                        .map(|(cell, w)| format!("{cell:<w$}"))
+                        .collect();
+                    writeln!(out, "{}", line.join("  ").trim_end()).unwrap();
+                };
+                write_row(columns.iter().map(|(_, h)| *h).collect());
+                for row in rows.iter() {
+                    write_row(row.iter().map(String::as_str).collect());
+                }
+            }
+            Format::Markdown => {
+                let escape = |s: &str| s.replace('|', "\\|").replace('\n', "<br>");
+                let headers: Vec<String> = columns.iter().map(|(_, h)| escape(h)).collect();
+                writeln!(out, "| {} |", headers.join(" | ")).unwrap();
+                writeln!(out, "|{}", "---|".repeat(columns.len())).unwrap();
+                for row in self.rows.iter() {
+                    let row: Vec<String> =
+                        cells(row, Cell::human).iter().map(|c| escape(c)).collect();
+                    writeln!(out, "| {} |", row.join(" | ")).unwrap();
+                }
+            }
+            Format::Csv => {
+                let keys: Vec<&str> = columns.iter().map(|(_, k)| *k).collect();
+                writeln!(out, "{}", keys.join(",")).unwrap();
+                for row in self.rows.iter() {
+                    writeln!(out, "{}", cells(row, Cell::csv).join(",")).unwrap();
+                }
+            }
+            Format::Json => {
+                let objects: Vec<String> = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        let fields: Vec<String> = columns
+                            .iter()
+                            .map(|(i, k)| format!("{}:{}", json_string(k), row[*i].json()))
+                            .collect();
+                        format!("{{{}}}", fields.join(","))
+                    })
+                    .collect();
+                writeln!(out, "[{}]", objects.join(",\n ")).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Size like `512 B` or `1.5 KiB`.
+pub fn human_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = "B";
+    for u in UNITS {
+        if value < 1024. {
+            break;
+        }
+        value /= 1024.;
+        unit = u;
+    }
+    format!("{value:.1} {unit}")
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(vec![
+            Column::human("Exercise"),
+            Column::data("day"),
+            Column::new("answer", "Answer"),
+            Column::new("duration_ns", "Time"),
+        ]);
+        table.push(vec![
+            "Dec 1".into(),
+            1u8.into(),
+            "a,\"b\"\nc".into(),
+            Duration::from_micros(2).into(),
+        ]);
+        table.push(vec!["Dec 2".into(), 2u8.into(), Cell::None, Cell::None]);
+        table
+    }
+
+    #[test]
+    fn csv_quotes_special_values() {
+        assert_eq!(
+            table().render(Format::Csv),
+            "day,answer,duration_ns\n1,\"a,\"\"b\"\"\nc\",2000\n2,,\n"
+        );
+        assert_eq!(Cell::from("a\rb").csv(), "\"a\rb\"");
+        assert_eq!(Cell::from("plain text").csv(), "plain text");
+        assert_eq!(Cell::List(vec!["x".into(), "y".into()]).csv(), "x;y");
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        assert_eq!(
+            table().render(Format::Json),
+            "[{\"day\":1,\"answer\":\"a,\\\"b\\\"\\nc\",\"duration_ns\":2000},\n \
+             {\"day\":2,\"answer\":null,\"duration_ns\":null}]\n"
+        );
+        assert_eq!(json_string("\\ \t \u{1}"), "\"\\\\ \\t \\u0001\"");
+        assert_eq!(Cell::Float(f64::NAN).json(), "null");
+        assert_eq!(
+            Table::new(vec![Column::data("a")]).render(Format::Json),
+            "[]\n"
+        );
+    }
+
+    #[test]
+    fn human_formats_skip_data_columns() {
+        let mut table = Table::new(vec![
+            Column::human("Exercise"),
+            Column::data("day"),
+            Column::new("answer", "Answer"),
+        ]);
+        table.push(vec!["Dec 1".into(), 1u8.into(), "a|b".into()]);
+        table.push(vec!["Dec 10".into(), 10u8.into(), Cell::None]);
+        assert_eq!(
+            table.render(Format::Text),
+            "Exercise  Answer\nDec 1     a|b\nDec 10    -\n"
+        );
+        assert_eq!(
+            table.render(Format::Markdown),
+            "| Exercise | Answer |\n|---|---|\n| Dec 1 | a\\|b |\n| Dec 10 | - |\n"
+        );
+    }
+
+    #[test]
+    fn human_bytes_units() {
+        assert_eq!(human_bytes(0), "0 B");
+        assert_eq!(human_bytes(1023), "1023 B");
+        assert_eq!(human_bytes(1024), "1.0 KiB");
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+        assert_eq!(human_bytes(14 << 20), "14.0 MiB");
+        assert_eq!(human_bytes(3 << 30), "3.0 GiB");
+        assert_eq!(human_bytes(5 << 40), "5.0 TiB");
+        assert_eq!(human_bytes(2048 << 40), "2048.0 TiB");
+    }
+}
@@ -0,0 +1,91 @@
+use crate::guard::SolveFn;
+use anyhow::Result;
+use aoc_core::Input;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Samples taken per second, not a multiple of the usual timer frequencies so that periodic work
+/// isn't sampled in lockstep.
+#[cfg_attr(not(unix), allow(dead_code))]
+const FREQUENCY: i32 = 997;
+
+/// The part is run again and again for at least this long, to get enough samples of fast parts.
+#[cfg_attr(not(unix), allow(dead_code))]
+const MIN_TIME: Duration = Duration::from_secs(1);
+
+/// Name of the thread running the part, whose samples are the only ones kept.
+#[cfg_attr(not(unix), allow(dead_code))]
+const THREAD_NAME: &str = "profiled";
+
+/// Outcome of the profiling of a part.
+pub struct Profile {
+    pub runs: u32,
+    pub samples: isize,
+    /// The samples in the pprof format, next to the flamegraph
+    pub pprof_path: PathBuf,
+}
+
+/// Profiles a part by sampling the stack of the thread running it, then writes a flamegraph of the
+/// samples to the SVG file, and the samples themselves to a `.pb` file next to it, for `pprof`.
+#[cfg(unix)]
+pub fn profile(solve: &SolveFn, input: &Arc<Input>, svg_path: &Path) -> Result<Profile> {
+    use crate::guard;
+    use anyhow::Context;
+    use pprof::protos::Message;
+    use std::fs::{self, File};
+    use std::thread;
+    use std::time::Instant;
+
+    let profiler = pprof::ProfilerGuardBuilder::default()
+        .frequency(FREQUENCY)
+        // Unwinding through these while they hold locks could deadlock
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .context("Could not start the profiler")?;
+
+    let (solve, input) = (solve.clone(), input.clone());
+    let runs = thread::Builder::new()
+        .name(THREAD_NAME.to_string())
+        .stack_size(guard::STACK_SIZE)
+        .spawn(move || {
+            let start = Instant::now();
+            let mut runs = 0;
+            while runs == 0 || start.elapsed() < MIN_TIME {
+                runs += 1;
+                if guard::run(&solve, &input, None).answer.is_err() {
+                    break;
+                }
+            }
+            runs
+        })
+        .context("Could not start the thread of the part")?
+        .join()
+        .map_err(|_| anyhow::anyhow!("The thread of the part died"))?;
+
+    let mut report = profiler.report().build()?;
+    drop(profiler);
+    report
+        .data
+        .retain(|frames, _| frames.thread_name == THREAD_NAME);
+
+    let svg = File::create(svg_path)
+        .with_context(|| format!("Could not create {}", svg_path.display()))?;
+    report.flamegraph(svg)?;
+    let pprof_path = svg_path.with_extension("pb");
+    let mut pprof = vec![];
+    report.pprof()?.encode(&mut pprof)?;
+    fs::write(&pprof_path, pprof)
+        .with_context(|| format!("Could not write {}", pprof_path.display()))?;
+
+    Ok(Profile {
+        runs,
+        samples: report.data.values().sum(),
+        pprof_path,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn profile(_solve: &SolveFn, _input: &Arc<Input>, _svg_path: &Path) -> Result<Profile> {
+    anyhow::bail!("Profiling is only supported on Unix")
+}
@@ -0,0 +1,178 @@
+use crate::bench::Baseline;
+use crate::output::{Cell, Column, Format, Table};
+use crate::verify::{self, Manifest, Status};
+use anyhow::{bail, Result};
+use aoc_core::AdventOfCodeRunnable;
+use std::time::Duration;
+
+pub static START_MARKER: &str = "<!-- progress:start -->";
+pub static END_MARKER: &str = "<!-- progress:end -->";
+
+/// Markdown table of the progress on each exercise: stars of the confirmed parts, parts whose
+/// answer matches the manifest (given up on after the timeout), and median solve times of the
+/// baseline.
+pub fn progress_table(
+    es: &[&'static dyn AdventOfCodeRunnable],
+    manifest: &mut Manifest,
+    baseline: Option<&Baseline>,
+    timeout: Option<Duration>,
+) -> Result<String> {
+    let mut rows = vec![];
+    for ex in es.iter().copied() {
+        let statuses = verify::verify(ex, manifest, false, timeout)?;
+        rows.push(progress_row(ex, &statuses, baseline)?);
+    }
+    Ok(render_table(rows))
+}
+
+fn render_table(rows: Vec<Vec<Cell>>) -> String {
+    let mut table = Table::new(vec![
+        Column::human("Day"),
+        Column::human("Title"),
+        Column::human("Stars"),
+        Column::human("Verified"),
+        Column::human("Part 1"),
+        Column::human("Part 2"),
+    ]);
+
+    for row in rows {
+        table.push(row);
+    }
+    table.render(Format::Markdown)
+}
+
+fn progress_row(
+    ex: &dyn AdventOfCodeRunnable,
+    statuses: &[Status],
+    baseline: Option<&Baseline>,
+) -> Result<Vec<Cell>> {
+    let id = ex.id();
+    let stars = "⭐".repeat([1, 2].iter().filter(|p| ex.is_confirmed(**p)).count());
+    let verified: Vec<String> = (1..)
+        .zip(statuses.iter())
+        .filter(|(_, s)| matches!(s, Status::Pass(_)))
+        .map(|(part, _)| part.to_string())
+        .collect();
+    let median = |part: u8| -> Result<Cell> {
+        let id = id.with_part(part)?;
+        Ok(baseline.and_then(|b| b.median(&id)).into())
+    };
+
+    Ok(vec![
+        format!(
+            "[{}/{}](https://adventofcode.com/{}/day/{})",
+            id.year(),
+            id.day(),
+            id.year(),
+            id.day()
+        )
+        .into(),
+        ex.title().into(),
+        stars.into(),
+        (!verified.is_empty()).then_some(verified).into(),
+        median(1)?,
+        median(2)?,
+    ])
+}
+
+/// Replaces what is between the markers of the progress section with the given table.
+pub fn replace_section(readme: &str, table: &str) -> Result<String> {
+    let (start, end) = match (readme.find(START_MARKER), readme.find(END_MARKER)) {
+        (Some(start), Some(end)) if start < end => (start + START_MARKER.len(), end),
+        _ => bail!("The README has no progress section; add the lines {START_MARKER} and {END_MARKER} where it should go"),
+    };
+    Ok(format!("{}\n{table}{}", &readme[..start], &readme[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_core::{Input, PuzzleId};
+    use std::fmt::{Display, Formatter};
+    use std::fs;
+
+    struct NoSpaceLeftOnDevice;
+
+    impl Display for NoSpaceLeftOnDevice {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Dec 7, 2022 - NoSpaceLeftOnDevice")
+        }
+    }
+
+    impl AdventOfCodeRunnable for NoSpaceLeftOnDevice {
+        fn id(&self) -> PuzzleId {
+            PuzzleId::new(2022, 7).unwrap()
+        }
+        fn title(&self) -> &'static str {
+            "No Space Left On Device"
+        }
+        fn tags(&self) -> &'static [&'static str] {
+            &[]
+        }
+        fn is_implemented(&self, _part: u8) -> bool {
+            true
+        }
+        fn is_confirmed(&self, part: u8) -> bool {
+            part == 1
+        }
+        fn run(&self, _input: &Input) -> Result<String> {
+            Ok(String::new())
+        }
+        fn run2(&self, _input: &Input) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    #[test]
+    fn renders_the_progress() {
+        let path = std::env::temp_dir().join(format!("aoc-readme-{}.txt", std::process::id()));
+        fs::write(&path, "2022/7/1 1500\n").unwrap();
+        let baseline = Baseline::load(&path);
+        fs::remove_file(&path).unwrap();
+        let baseline = baseline.unwrap();
+
+        let statuses = [
+            Status::Pass("95437".into()),
+            Status::Error("timed out".into()),
+        ];
+        let verified = progress_row(&NoSpaceLeftOnDevice, &statuses, Some(&baseline)).unwrap();
+        let statuses = [Status::Missing("95437".into()), Status::NoInput];
+        let unverified = progress_row(&NoSpaceLeftOnDevice, &statuses, None).unwrap();
+        assert_eq!(
+            render_table(vec![verified, unverified]),
+            "| Day | Title | Stars | Verified | Part 1 | Part 2 |\n\
+            |---|---|---|---|---|---|\n\
+            | [2022/7](https://adventofcode.com/2022/day/7) | No Space Left On Device | ⭐ | 1 | 1.5µs | - |\n\
+            | [2022/7](https://adventofcode.com/2022/day/7) | No Space Left On Device | ⭐ | - | - | - |\n"
+        );
+    }
+
+    #[test]
+    fn replaces_the_progress_section() {
+        let readme = format!("# AoC\n{START_MARKER}\nold\n{END_MARKER}\nMore\n");
+        let updated = replace_section(&readme, "| new |\n").unwrap();
+        assert_eq!(
+            updated,
+            format!("# AoC\n{START_MARKER}\n| new |\n{END_MARKER}\nMore\n")
+        );
+        // Updating it again changes nothing
+        assert_eq!(replace_section(&updated, "| new |\n").unwrap(), updated);
+    }
+
+    #[test]
+    fn needs_the_markers_in_order() {
+        for readme in [
+            "# AoC\n".to_string(),
+            format!("{START_MARKER}\n"),
+            format!("{END_MARKER}\n"),
+            format!("{END_MARKER}\n{START_MARKER}\n"),
+        ] {
+            let e = replace_section(&readme, "| new |\n").unwrap_err();
+            assert!(
+                e.to_string()
+                    .starts_with("The README has no progress section"),
+                "{e}"
+            );
+        }
+    }
+}
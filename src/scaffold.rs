@@ -0,0 +1,159 @@
+use anyhow::{bail, Context, Result};
+use aoc_core::PuzzleId;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Template of a new day, with `{{year}}`, `{{day}}`, `{{title}}` and `{{example}}` placeholders.
+pub static DEFAULT_TEMPLATE: &str = include_str!("template.rs");
+
+/// What can be gathered about a puzzle to fill a template.
+pub struct Puzzle {
+    pub id: PuzzleId,
+    pub title: String,
+    pub example: String,
+}
+
+impl Puzzle {
+    /// Fetches the title and example of the puzzle from its page, falling back to `Day <day>` and
+    /// an empty example when the page is unavailable, e.g. before the puzzle is released.
+    pub fn fetch(id: PuzzleId) -> Self {
+        // Logged out, the page has the title and the first example, which is all there is to get
+        let page = aoc_core::get_puzzle_page(&id, None);
+        if let Err(e) = &page {
+            println!("Could not fetch the puzzle page of {id}, it will be left untitled: {e}");
+        }
+        let page = page.unwrap_or_default();
+
+        Self {
+            id,
+            title: title_from_page(&page).unwrap_or_else(|| format!("Day {}", id.day())),
+            example: example_from_page(&page).unwrap_or_default(),
+        }
+    }
+
+    pub fn instantiate(&self, template: &str) -> String {
+        template
+            .replace("{{year}}", &self.id.year().to_string())
+            .replace("{{day}}", &self.id.day().to_string())
+            .replace("{{title}}", &escape_str(&self.title))
+            .replace("{{example}}", &escape_str(&self.example))
+    }
+}
+
+/// Module of the day's solution, which the build script picks up.
+fn module_path(id: &PuzzleId) -> PathBuf {
+    PathBuf::from(format!("src/aoc_{}_{}.rs", id.year(), id.day()))
+}
+
+/// Writes the module of a new day, refusing to overwrite an existing one.
+pub fn create_module(puzzle: &Puzzle, template: &str) -> Result<PathBuf> {
+    let path = module_path(&puzzle.id);
+    if !Path::new("src").is_dir() {
+        bail!("No src directory here; run this command from the root of the crate");
+    }
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+
+    fs::write(&path, puzzle.instantiate(template))
+        .with_context(|| format!("Could not write {}", path.display()))?;
+    Ok(path)
+}
+
+/// The title in the page's `<h2>--- Day 1: Calorie Counting ---</h2>`.
+fn title_from_page(page: &str) -> Option<String> {
+    let start = page.find("<h2>")? + "<h2>".len();
+    let h2 = &page[start..start + page[start..].find("</h2>")?];
+    let title = h2.trim_matches(|c: char| c == '-' || c.is_whitespace());
+    let title = title.split_once(": ").map_or(title, |(_, t)| t);
+    Some(unescape_html(title))
+}
+
+/// The first `<pre><code>` block of the page, which is the example of nearly all puzzles.
+fn example_from_page(page: &str) -> Option<String> {
+    let start = page.find("<pre><code>")? + "<pre><code>".len();
+    let code = &page[start..start + page[start..].find("</code></pre>")?];
+
+    // Drop the emphasis tags within the example
+    let mut text = String::with_capacity(code.len());
+    let mut in_tag = false;
+    for c in code.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    Some(unescape_html(text.trim_end_matches('\n')))
+}
+
+pub fn unescape_html(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Escapes a text to be put between the quotes of a string literal.
+fn escape_str(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Start of a puzzle page, as served by adventofcode.com.
+    static PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 13: Distress Signal: &quot;Packets&quot; ---</h2><p>You climb the hill.</p>
+<p>For example:</p>
+<pre><code>[1,1,3,1,1]
+<em>[1,1,5,1,1]</em>
+
+<em><code>a &lt;b&gt; &amp; "c" \d</code></em>
+</code></pre>
+<p>Then:</p>
+<pre><code>not the example</code></pre>
+</article>
+</main>"#;
+
+    #[test]
+    fn title() {
+        assert_eq!(
+            title_from_page(PAGE).unwrap(),
+            "Distress Signal: \"Packets\""
+        );
+        assert_eq!(title_from_page("<h2>Day 1</h2>").unwrap(), "Day 1");
+        assert_eq!(title_from_page("<p>Not released yet</p>"), None);
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(
+            example_from_page(PAGE).unwrap(),
+            "[1,1,3,1,1]\n[1,1,5,1,1]\n\na <b> & \"c\" \\d"
+        );
+        assert_eq!(example_from_page("<pre><code>unterminated"), None);
+        assert_eq!(unescape_html("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn instantiates_string_literals() {
+        let puzzle = Puzzle {
+            id: PuzzleId::new(2022, 13).unwrap(),
+            title: title_from_page(PAGE).unwrap(),
+            example: example_from_page(PAGE).unwrap(),
+        };
+        let module = puzzle.instantiate(
+            "// {{year}}/{{day}}\nconst TITLE: &str = \"{{title}}\";\nconst INPUT: &str = \"{{example}}\";",
+        );
+        assert_eq!(
+            module,
+            "// 2022/13\n\
+            const TITLE: &str = \"Distress Signal: \\\"Packets\\\"\";\n\
+            const INPUT: &str = \"[1,1,3,1,1]\n[1,1,5,1,1]\n\na <b> & \\\"c\\\" \\\\d\";"
+        );
+    }
+}
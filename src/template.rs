@@ -1,4 +1,8 @@
-﻿pub fn run(input: &str) -> anyhow::Result<String> {
+﻿use macro_support::aoc_example;
+
+pub const TITLE: &str = "{{title}}";
+
+pub fn run(input: &str) -> anyhow::Result<String> {
     for line in input.lines() {}
     Ok("".to_string())
 }
@@ -7,3 +11,11 @@ pub fn run2(input: &str) -> anyhow::Result<String> {
     for line in input.lines() {}
     Ok("".to_string())
 }
+
+aoc_example! {
+    input = "
+{{example}}",
+    // Fails until replaced by the answers given with the example
+    part1 = "TODO",
+    part2 = "TODO",
+}
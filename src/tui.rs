@@ -0,0 +1,603 @@
+use crate::guard::{self, PartError};
+use anyhow::{Context, Result};
+use aoc_core::{AdventOfCodeRunnable, PuzzleId};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Width and height of a day of the calendar, borders included.
+const CELL: (u16, u16) = (12, 4);
+
+/// How often the screen is refreshed while waiting for keys, e.g. to show the answer of a part.
+const REFRESH: Duration = Duration::from_millis(100);
+
+/// Shows the calendar of the year of the puzzle, with the puzzle selected, until the user quits.
+/// Parts run on a worker thread, given up on after the timeout.
+pub fn run(id: PuzzleId, session_id: Option<String>, timeout: Option<Duration>) -> Result<()> {
+    terminal::enable_raw_mode()?;
+    let mut restore = Restore(terminal_output()?);
+    execute!(restore.0, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(terminal_output()?))?;
+    let (_capture, output) = match capture_output() {
+        Ok((capture, output)) => (Some(capture), output),
+        // Better not capture anything than lose the standard output or error
+        Err(_) => (None, mpsc::channel().1),
+    };
+
+    let mut app = App::new(id, session_id, timeout, output);
+    while !app.quit {
+        app.receive_output();
+        app.receive_run();
+        app.receive_puzzles();
+        terminal.draw(|frame| app.draw(frame))?;
+        if !event::poll(REFRESH)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                let ctrl_c =
+                    key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+                match ctrl_c {
+                    true => app.quit = true,
+                    false => app.on_key(key.code),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Gives the terminal back to the shell, even if the dashboard fails.
+struct Restore<W: Write>(W);
+
+impl<W: Write> Drop for Restore<W> {
+    fn drop(&mut self) {
+        let _ = execute!(self.0, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// The terminal, through a descriptor of its own: the standard output is redirected during the
+/// session, to capture what the parts print.
+#[cfg(unix)]
+fn terminal_output() -> Result<std::fs::File> {
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: dup has no precondition, and the new descriptor is owned by the file
+    let fd = unsafe { libc::dup(1) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error()).context("Could not duplicate the standard output");
+    }
+    Ok(unsafe { std::fs::File::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn terminal_output() -> Result<io::Stdout> {
+    Ok(io::stdout())
+}
+
+/// A part running on the worker thread, which sends its outcome once it's done.
+struct Running {
+    id: PuzzleId,
+    part: u8,
+    outcome: Receiver<guard::Run>,
+}
+
+struct App {
+    year: u16,
+    /// Selected day
+    day: u8,
+    session_id: Option<String>,
+    timeout: Option<Duration>,
+    running: Option<Running>,
+    /// Time taken by the last run of each day
+    last_runs: [Option<Duration>; PuzzleId::LAST_DAY as usize],
+    log: Vec<Line<'static>>,
+    show_puzzle: bool,
+    /// Text of the puzzles already loaded, or being loaded, by day
+    puzzles: HashMap<u8, String>,
+    /// Puzzles loaded by the threads downloading them, by day
+    loaded_tx: Sender<(u8, String)>,
+    loaded: Receiver<(u8, String)>,
+    /// Lines printed by the parts
+    output: Receiver<String>,
+    scroll: u16,
+    quit: bool,
+}
+
+impl App {
+    fn new(
+        id: PuzzleId,
+        session_id: Option<String>,
+        timeout: Option<Duration>,
+        output: Receiver<String>,
+    ) -> Self {
+        let (loaded_tx, loaded) = mpsc::channel();
+        Self {
+            year: id.year(),
+            day: id.day(),
+            session_id,
+            timeout,
+            running: None,
+            last_runs: [None; PuzzleId::LAST_DAY as usize],
+            log: vec![],
+            show_puzzle: false,
+            puzzles: HashMap::new(),
+            loaded_tx,
+            loaded,
+            output,
+            scroll: 0,
+            quit: false,
+        }
+    }
+
+    fn id(&self, day: u8) -> PuzzleId {
+        PuzzleId::new(self.year, day).expect("days of the calendar are valid")
+    }
+
+    fn exercise(&self, day: u8) -> Option<&'static dyn AdventOfCodeRunnable> {
+        aoc_core::registry().get(&self.id(day))
+    }
+
+    fn on_key(&mut self, key: KeyCode) {
+        let day = self.day;
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Char('h') => self.day = day.saturating_sub(1).max(1),
+            KeyCode::Right | KeyCode::Char('l') => self.day = (day + 1).min(PuzzleId::LAST_DAY),
+            KeyCode::Up | KeyCode::Char('k') if day > 5 => self.day -= 5,
+            KeyCode::Down | KeyCode::Char('j') if day + 5 <= PuzzleId::LAST_DAY => self.day += 5,
+            KeyCode::Enter | KeyCode::Char('1') => self.run_part(1),
+            KeyCode::Char('2') => self.run_part(2),
+            KeyCode::Char('p') => self.show_puzzle = !self.show_puzzle,
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            _ => {}
+        }
+
+        if self.day != day {
+            self.scroll = 0;
+        }
+        if self.show_puzzle && !self.puzzles.contains_key(&self.day) {
+            self.load_puzzle();
+        }
+    }
+
+    /// Downloads the puzzle of the selected day on a thread of its own, not to freeze the screen.
+    fn load_puzzle(&mut self) {
+        let (day, id, session_id) = (self.day, self.id(self.day), self.session_id.clone());
+        let tx = self.loaded_tx.clone();
+        let spawned = thread::Builder::new()
+            .name("puzzle".to_string())
+            .spawn(move || {
+                let text = match aoc_core::get_puzzle_page(&id, session_id.as_deref()) {
+                    Ok(page) => page_text(&page),
+                    Err(e) => format!("Could not get the puzzle: {e}"),
+                };
+                let _ = tx.send((day, text));
+            });
+        let text = match spawned {
+            Ok(_) => "Loading the puzzle...".to_string(),
+            Err(e) => format!("Could not start downloading the puzzle: {e}"),
+        };
+        self.puzzles.insert(day, text);
+    }
+
+    fn receive_puzzles(&mut self) {
+        while let Ok((day, text)) = self.loaded.try_recv() {
+            self.puzzles.insert(day, text);
+        }
+    }
+
+    /// Logs what the parts printed, including the ones given up on which are still running.
+    fn receive_output(&mut self) {
+        while let Ok(line) = self.output.try_recv() {
+            self.log(format!("  {line}"), Color::DarkGray);
+        }
+    }
+
+    fn run_part(&mut self, part: u8) {
+        let id = self.id(self.day);
+        if let Some(running) = &self.running {
+            let msg = format!(
+                "{id}, part {part}: wait for part {} of {} to finish",
+                running.part, running.id
+            );
+            self.log(msg, Color::DarkGray);
+            return;
+        }
+        let ex = match self.exercise(self.day) {
+            Some(ex) if ex.is_implemented(part) => ex,
+            _ => {
+                self.log(
+                    format!("{id}, part {part}: not implemented"),
+                    Color::DarkGray,
+                );
+                return;
+            }
+        };
+        if !ex.is_input_cached() && self.session_id.is_none() && !aoc_core::settings().offline {
+            self.log(
+                format!("{id}: no session ID to download the input"),
+                Color::Red,
+            );
+            return;
+        }
+
+        let (session_id, timeout) = (self.session_id.clone().unwrap_or_default(), self.timeout);
+        let (tx, rx) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("worker".to_string())
+            .stack_size(guard::STACK_SIZE)
+            .spawn(move || {
+                // Downloading the input can take a while too
+                let outcome = match ex.get_input(&session_id, part == 2) {
+                    Ok(input) => guard::run(&guard::solution(ex, part), &Arc::new(input), timeout),
+                    Err(e) => {
+                        guard::Run::failed(PartError::Failed(e.context("Could not get the input")))
+                    }
+                };
+                let _ = io::stdout().flush();
+                let _ = tx.send(outcome);
+            });
+        match spawned {
+            Ok(_) => {
+                self.log(format!("{id}, part {part}: running..."), Color::DarkGray);
+                self.running = Some(Running {
+                    id,
+                    part,
+                    outcome: rx,
+                });
+            }
+            Err(e) => self.log(format!("{id}: could not start the worker: {e}"), Color::Red),
+        }
+    }
+
+    /// Logs the outcome of the running part, once it's done.
+    fn receive_run(&mut self) {
+        let outcome = match self.running.as_ref().map(|r| r.outcome.try_recv()) {
+            None | Some(Err(TryRecvError::Empty)) => return,
+            Some(outcome) => outcome,
+        };
+        let Running { id, part, .. } = self.running.take().expect("a part is running");
+        let run = match outcome {
+            Ok(run) => run,
+            Err(_) => return self.log(format!("{id}: the worker died"), Color::Red),
+        };
+
+        match run.answer {
+            Ok(answer) => {
+                let elapsed = run.elapsed;
+                self.last_runs[id.day() as usize - 1] = Some(elapsed);
+                self.log(
+                    format!("{id}, part {part}: {answer} ({elapsed:?})"),
+                    Color::Reset,
+                )
+            }
+            Err(e) => self.log(format!("{id}, part {part}: {e}"), Color::Red),
+        }
+    }
+
+    fn log(&mut self, msg: String, color: Color) {
+        for line in msg.lines() {
+            self.log.push(Line::styled(line.to_string(), color));
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Length(CELL.0 * 5 + 2), Constraint::Min(0)])
+                .areas(main);
+        let [calendar, details] =
+            Layout::vertical([Constraint::Length(CELL.1 * 5 + 2), Constraint::Min(0)]).areas(left);
+
+        self.draw_calendar(frame, calendar);
+        self.draw_details(frame, details);
+        if self.show_puzzle {
+            let [puzzle, log] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(12)]).areas(right);
+            self.draw_puzzle(frame, puzzle);
+            self.draw_log(frame, log);
+        } else {
+            self.draw_log(frame, right);
+        }
+
+        frame.render_widget(
+            Line::from(
+                " ←↑↓→ select   1/Enter run part 1   2 run part 2   p puzzle   PgUp/PgDn scroll   \
+                q quit",
+            )
+            .dark_gray(),
+            help,
+        );
+    }
+
+    fn draw_calendar(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(format!(" Advent of Code {} ", self.year));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        for day in 1..=PuzzleId::LAST_DAY {
+            let (col, row) = ((day - 1) % 5, (day - 1) / 5);
+            let cell = Rect::new(
+                inner.x + col as u16 * CELL.0,
+                inner.y + row as u16 * CELL.1,
+                CELL.0,
+                CELL.1,
+            )
+            .intersection(inner);
+            frame.render_widget(self.day_cell(day), cell);
+        }
+    }
+
+    /// Stars of the confirmed parts (☆ for the implemented ones), whether the input is cached, and
+    /// the time of the last run.
+    fn day_cell(&self, day: u8) -> Paragraph<'static> {
+        let ex = self.exercise(day);
+        let mut status: Vec<Span> = [1, 2]
+            .map(|part| match ex {
+                Some(ex) if ex.is_confirmed(part) => "★".yellow(),
+                Some(ex) if ex.is_implemented(part) => "☆".into(),
+                _ => "·".dark_gray(),
+            })
+            .into();
+        if aoc_core::cached_input_path(&self.id(day)).is_file() {
+            status.push(" input".green());
+        }
+        let last_run = match self.last_runs[day as usize - 1] {
+            Some(elapsed) => format!("{elapsed:.1?}"),
+            None => String::new(),
+        };
+
+        let border = match (day == self.day, ex) {
+            (true, _) => Style::new().yellow().add_modifier(Modifier::BOLD),
+            (false, Some(_)) => Style::new(),
+            (false, None) => Style::new().dark_gray(),
+        };
+        Paragraph::new(vec![Line::from(status), Line::from(last_run)]).block(
+            Block::bordered()
+                .title(format!(" {day} "))
+                .border_style(border),
+        )
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let id = self.id(self.day);
+        let lines = match self.exercise(self.day) {
+            Some(ex) => {
+                let parts: Vec<String> = [1, 2]
+                    .into_iter()
+                    .filter(|p| ex.is_implemented(*p))
+                    .map(|p| match ex.is_confirmed(p) {
+                        true => format!("{p} (confirmed)"),
+                        false => p.to_string(),
+                    })
+                    .collect();
+                vec![
+                    Line::from(ex.title()).bold(),
+                    Line::from(format!("Parts: {}", parts.join(", "))),
+                    Line::from(format!("Tags: {}", ex.tags().join(", "))),
+                ]
+            }
+            None => vec![Line::from("Not implemented").dark_gray()],
+        };
+        let input = aoc_core::cached_input_path(&id);
+        let input = match input.is_file() {
+            true => format!("Input: {}", input.display()),
+            false => "Input: not downloaded yet".to_string(),
+        };
+
+        let mut text = Text::from(lines);
+        text.push_line(input);
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(format!(" Dec {}, {} ", id.day(), id.year()))),
+            area,
+        );
+    }
+
+    fn draw_puzzle(&self, frame: &mut Frame, area: Rect) {
+        let text = self.puzzles.get(&self.day).map_or("", String::as_str);
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0))
+                .block(Block::bordered().title(format!(" Puzzle of Dec {} ", self.day))),
+            area,
+        );
+    }
+
+    /// The end of the log, which lines aren't wrapped so that the last ones are always visible.
+    fn draw_log(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title(" Output ");
+        let height = block.inner(area).height as usize;
+        let start = self.log.len().saturating_sub(height);
+        frame.render_widget(
+            Paragraph::new(self.log[start..].to_vec()).block(block),
+            area,
+        );
+    }
+}
+
+/// The text of the puzzle's `<main>`, with paragraphs and list items on their own lines.
+fn page_text(page: &str) -> String {
+    let main = match (page.find("<main>"), page.find("</main>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+
+    let mut text = String::with_capacity(main.len());
+    let mut rest = main;
+    let mut in_pre = false;
+    while let Some(start) = rest.find('<') {
+        // Line breaks between the tags are only meaningful in the examples
+        let between = &rest[..start];
+        if in_pre || !(between.trim().is_empty() && between.contains('\n')) {
+            text.push_str(between);
+        }
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = rest[start + 1..end].split_whitespace().next().unwrap_or("");
+        in_pre = match tag {
+            "pre" => true,
+            "/pre" => false,
+            _ => in_pre,
+        };
+        match tag {
+            "/p" | "/h2" | "/pre" | "/ul" | "br" | "br/" => text.push_str("\n\n"),
+            "li" => text.push_str("- "),
+            "/li" => text.push('\n'),
+            _ => {}
+        }
+        rest = &rest[end + 1..];
+    }
+
+    let text = crate::scaffold::unescape_html(&text);
+    let mut lines: Vec<&str> = vec![];
+    for line in text.lines().map(str::trim_end) {
+        // At most one blank line in a row
+        if !(line.is_empty() && lines.last().is_none_or(|l| l.is_empty())) {
+            lines.push(line);
+        }
+    }
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Redirects the standard output and error to a pipe for the whole session, and gives back the
+/// lines printed, e.g. the debug prints of the solutions. Parts given up on keep running, so they
+/// could print anytime.
+#[cfg(unix)]
+fn capture_output() -> io::Result<(Capture, Receiver<String>)> {
+    use std::io::{BufRead, BufReader};
+    use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+
+    let mut fds = [0; 2];
+    // SAFETY: the descriptors of the pipe are owned right after
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let (reader, writer) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    let capture = Capture {
+        saved: [
+            io::stdout().as_fd().try_clone_to_owned()?,
+            io::stderr().as_fd().try_clone_to_owned()?,
+        ],
+    };
+
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("output".to_string())
+        .spawn(move || {
+            let mut reader = BufReader::new(std::fs::File::from(reader));
+            let mut line = vec![];
+            while matches!(reader.read_until(b'\n', &mut line), Ok(n) if n > 0) {
+                let _ = tx.send(String::from_utf8_lossy(&line).trim_end().to_string());
+                line.clear();
+            }
+        })?;
+
+    let _ = io::stdout().flush();
+    for fd in [1, 2] {
+        // SAFETY: plain descriptor juggling, the original descriptors are restored on drop
+        if unsafe { libc::dup2(writer.as_raw_fd(), fd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok((capture, rx))
+}
+
+#[cfg(not(unix))]
+fn capture_output() -> io::Result<(Capture, Receiver<String>)> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Gives the standard output and error back to the terminal when dropped.
+struct Capture {
+    #[cfg(unix)]
+    saved: [std::os::fd::OwnedFd; 2],
+}
+
+impl Drop for Capture {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+
+            let _ = io::stdout().flush();
+            for (saved, fd) in self.saved.iter().zip([1, 2]) {
+                // SAFETY: the saved descriptors are still open
+                unsafe { libc::dup2(saved.as_raw_fd(), fd) };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_text_of_main() {
+        let page = r#"<html><nav>Events</nav><main>
+<article class="day-desc"><h2>--- Day 6: Tuning &amp; Trouble ---</h2><p>The <em>signal</em> is
+garbled.</p>
+<p>For example:</p>
+<pre><code>mjqj
+  &lt;pqm&gt;
+
+jp
+</code></pre>
+<ul>
+<li><code>bvwb</code>: first at <code>5</code></li>
+<li>nppd: first at 6</li>
+</ul>
+<p>Line<br/>break</p>
+</article>
+</main><footer>Sponsors</footer></html>"#;
+        assert_eq!(
+            page_text(page),
+            "--- Day 6: Tuning & Trouble ---\n\
+            \n\
+            The signal is\n\
+            garbled.\n\
+            \n\
+            For example:\n\
+            \n\
+            mjqj\n  <pqm>\n\njp\n\
+            \n\
+            - bvwb: first at 5\n\
+            - nppd: first at 6\n\
+            \n\
+            Line\n\
+            \n\
+            break"
+        );
+    }
+
+    #[test]
+    fn page_text_without_main() {
+        assert_eq!(page_text("<p>Please log in.</p>"), "Please log in.");
+        assert_eq!(page_text("</main>unbalanced<main>"), "unbalanced");
+        assert_eq!(page_text("<p>Unterminated <em"), "Unterminated");
+    }
+}
@@ -0,0 +1,249 @@
+use crate::guard;
+use anyhow::{Context, Result};
+use aoc_core::{AdventOfCodeRunnable, Input, PuzzleId};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub static DEFAULT_MANIFEST: &str = "answers.txt";
+
+/// Known-correct answers, for a given input of each part, saved as lines like
+/// `2022/7/1 <SHA-256 of the input> 95437`. Backslashes and line breaks of answers are escaped as
+/// `\\`, `\n` and `\r`.
+#[derive(Default)]
+pub struct Manifest(BTreeMap<(PuzzleId, String), String>);
+
+impl Manifest {
+    /// Loads the manifest, which is empty if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut manifest = Self::default();
+        if !path.exists() {
+            return Ok(manifest);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read the manifest {}", path.display()))?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let mut fields = line.splitn(3, ' ');
+            let (id, hash, answer) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(id), Some(hash), Some(answer)) => (id, hash, answer),
+                _ => anyhow::bail!("Invalid manifest line {line:?}"),
+            };
+            manifest
+                .0
+                .insert((id.parse()?, hash.to_string()), unescape(answer)?);
+        }
+        Ok(manifest)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content: String = self
+            .0
+            .iter()
+            .map(|((id, hash), answer)| format!("{id} {hash} {}\n", escape(answer)))
+            .collect();
+        fs::write(path, content)
+            .with_context(|| format!("Could not write the manifest {}", path.display()))
+    }
+
+    pub fn get(&self, id: &PuzzleId, input_hash: &str) -> Option<&str> {
+        self.0
+            .get(&(*id, input_hash.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, id: PuzzleId, input_hash: String, answer: String) {
+        self.0.insert((id, input_hash), answer);
+    }
+}
+
+fn escape(answer: &str) -> String {
+    let mut escaped = String::with_capacity(answer.len());
+    for c in answer.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> Result<String> {
+    let mut answer = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\\') => answer.push('\\'),
+                Some('n') => answer.push('\n'),
+                Some('r') => answer.push('\r'),
+                _ => anyhow::bail!("Invalid escape in the manifest answer {field:?}"),
+            },
+            c => answer.push(c),
+        }
+    }
+    Ok(answer)
+}
+
+pub fn input_hash(input: &Input) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Outcome of the verification of one part.
+pub enum Status {
+    Pass(String),
+    Fail {
+        expected: String,
+        got: String,
+    },
+    Error(String),
+    /// No known answer for this input, only the one given by the solution
+    Missing(String),
+    NotImplemented,
+    /// No cached input to run the part with
+    NoInput,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Pass(_) => "pass",
+            Status::Fail { .. } => "FAIL",
+            Status::Error(_) => "ERROR",
+            Status::Missing(_) => "missing",
+            Status::NotImplemented => "-",
+            Status::NoInput => "no input",
+        }
+    }
+
+    /// Name of the status in the data formats.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Status::Pass(_) => "pass",
+            Status::Fail { .. } => "fail",
+            Status::Error(_) => "error",
+            Status::Missing(_) => "missing",
+            Status::NotImplemented => "not_implemented",
+            Status::NoInput => "no_input",
+        }
+    }
+
+    pub fn expected(&self) -> Option<&str> {
+        match self {
+            Status::Pass(answer)
+            | Status::Fail {
+                expected: answer, ..
+            } => Some(answer),
+            _ => None,
+        }
+    }
+
+    pub fn answer(&self) -> Option<&str> {
+        match self {
+            Status::Pass(answer) | Status::Fail { got: answer, .. } | Status::Missing(answer) => {
+                Some(answer)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Runs the parts of the exercise against its cached input and compares their answers with the
+/// manifest. Answers of parts missing from the manifest are added to it if `record` is set.
+pub fn verify(
+    ex: &'static dyn AdventOfCodeRunnable,
+    manifest: &mut Manifest,
+    record: bool,
+    timeout: Option<Duration>,
+) -> Result<[Status; 2]> {
+    let path = aoc_core::cached_input_path(&ex.id());
+    if !path.is_file() {
+        return Ok([Status::NoInput, Status::NoInput]);
+    }
+    let input = Arc::new(Input::open(&path)?);
+    let hash = input_hash(&input);
+
+    let mut statuses = [Status::NotImplemented, Status::NotImplemented];
+    for part in [1, 2].into_iter().filter(|p| ex.is_implemented(*p)) {
+        let id = ex.id().with_part(part)?;
+        let answer = match guard::run(&guard::solution(ex, part), &input, timeout).answer {
+            Ok(answer) => answer,
+            Err(e) => {
+                statuses[part as usize - 1] = Status::Error(e.to_string());
+                continue;
+            }
+        };
+
+        statuses[part as usize - 1] = match manifest.get(&id, &hash) {
+            Some(expected) if expected == answer => Status::Pass(answer),
+            Some(expected) => Status::Fail {
+                expected: expected.to_string(),
+                got: answer,
+            },
+            None => {
+                if record {
+                    manifest.insert(id, hash.clone(), answer.clone());
+                }
+                Status::Missing(answer)
+            }
+        };
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_round_trip() {
+        let id = |s: &str| s.parse::<PuzzleId>().unwrap();
+        let answers = [
+            ("2022/1/1", "24000"),
+            ("2022/5/1", "CMZ"),
+            ("2022/10/2", "##..\n#..#\n"),
+            ("2022/10/1", "a\\nb"),
+            ("2022/11/1", "C:\\dir\\"),
+            ("2022/11/2", "line\r\nend with spaces "),
+        ];
+        let mut manifest = Manifest::default();
+        for (i, (part, answer)) in answers.iter().enumerate() {
+            manifest.insert(id(part), format!("{i:064x}"), answer.to_string());
+        }
+
+        let path = std::env::temp_dir().join(format!("aoc-manifest-{}.txt", std::process::id()));
+        manifest.save(&path).unwrap();
+        let loaded = Manifest::load(&path);
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(content.lines().count(), answers.len());
+        for (i, (part, answer)) in answers.iter().enumerate() {
+            assert_eq!(loaded.get(&id(part), &format!("{i:064x}")), Some(*answer));
+        }
+        assert_eq!(loaded.get(&id("2022/1/2"), &format!("{:064x}", 0)), None);
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("a\\nb\nc"), "a\\\\nb\\nc");
+        assert_eq!(unescape("a\\\\nb\\nc").unwrap(), "a\\nb\nc");
+        assert!(unescape("trailing\\").is_err());
+        assert!(unescape("\\t").is_err());
+    }
+
+    #[test]
+    fn missing_manifest_is_empty() {
+        let path = Path::new("no/such/manifest.txt");
+        assert!(Manifest::load(path).unwrap().0.is_empty());
+    }
+}